@@ -1,5 +1,4 @@
 use std::env;
-use std::path::Path;
 
 fn main() {
     let toolchain = install_packages();
@@ -7,7 +6,8 @@ fn main() {
     println!("Building with {:#?}", out_dir);
 
     let tailwind_file = out_dir + "/tailwind.css";
-    std::process::Command::new(toolchain)
+    // Wait for the stylesheet, since the binary includes it with `include_str!`.
+    let status = std::process::Command::new(toolchain)
         .args([
             "tailwind",
             "-i",
@@ -19,27 +19,33 @@ fn main() {
             "--minify",
         ])
         .env("NODE_ENV", "production")
-        .spawn()
+        .status()
         .unwrap();
+    if !status.success() {
+        println!("cargo:warning=tailwind exited with {status}");
+    }
 }
 
 fn install_packages() -> &'static str {
     let npm = if_windows("npm.cmd", "npm");
     let npx = if_windows("npx.cmd", "npx");
 
-    match std::process::Command::new(npm).arg("install").spawn() {
+    match std::process::Command::new(npm).arg("install").status() {
+        Ok(status) if !status.success() => {
+            println!("cargo:warning=npm install exited with {status}");
+            npx
+        }
         Ok(_) => npx,
         Err(e) => panic!("ERROR: Npm or Yarn installation is needed.\n{e}"),
     }
 }
 
-const fn if_windows(windows: &'static str, unix: &'static str) -> &'static str {
-    #[cfg(windows)]
-    {
-        windows
-    }
-    #[cfg(unix)]
-    {
-        unix
-    }
+#[cfg(windows)]
+const fn if_windows(windows: &'static str, _unix: &'static str) -> &'static str {
+    windows
+}
+
+#[cfg(not(windows))]
+const fn if_windows(_windows: &'static str, unix: &'static str) -> &'static str {
+    unix
 }
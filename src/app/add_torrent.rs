@@ -0,0 +1,186 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::io_icons::IoCloseOutline;
+use dioxus_free_icons::Icon;
+
+use crate::app::labels::parse_labels;
+use crate::app::use_store;
+use crate::transmission::client::{Priority, TorrentAddRequest, TorrentAddResult};
+
+/// Id of the file input. `main.js` reads the selected file and writes it base64-encoded into the
+/// hidden input with the same id plus `-base64`, which sends it to us through the LiveView socket.
+const FILE_INPUT_ID: &str = "rt-add-torrent-file";
+
+#[inline_props]
+pub fn AddTorrentDialog<'a>(cx: Scope, onclose: EventHandler<'a, ()>) -> Element<'a> {
+    let store = use_store(cx);
+    let magnet = use_state(cx, || "".to_string());
+    let metainfo = use_state(cx, || "".to_string());
+    let download_dir = use_state(cx, || "".to_string());
+    let labels = use_state(cx, || "".to_string());
+    let priority = use_state(cx, || Priority::Normal);
+    let paused = use_state(cx, || false);
+    let adding = use_state(cx, || false);
+    let message = use_state::<Option<String>>(cx, || None);
+
+    // Labels in use that haven't been typed yet.
+    let typed_labels = parse_labels(labels.get());
+    let suggestions: Vec<String> = store
+        .labels()
+        .into_iter()
+        .filter(|label| !typed_labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+        .collect();
+
+    let can_submit = !*adding.get() && (!magnet.trim().is_empty() || !metainfo.is_empty());
+
+    let submit = move |_| {
+        let mut request = if metainfo.is_empty() {
+            TorrentAddRequest::filename(magnet.trim().to_string())
+        } else {
+            TorrentAddRequest::metainfo(metainfo.get().clone())
+        };
+        if !download_dir.trim().is_empty() {
+            request.download_dir = Some(download_dir.trim().to_string());
+        }
+        let labels = parse_labels(labels.get());
+        if !labels.is_empty() {
+            request.labels = Some(labels);
+        }
+        request.paused = Some(*paused.get());
+        request.bandwidth_priority = Some(*priority.get());
+
+        let store = store.clone();
+        let adding = adding.to_owned();
+        let message = message.to_owned();
+        let magnet = magnet.to_owned();
+        let metainfo = metainfo.to_owned();
+        adding.set(true);
+        cx.spawn(async move {
+            let result = match store.client().torrent_add(request).await {
+                Ok(TorrentAddResult::Added(torrent)) => {
+                    store.refresh();
+                    magnet.set("".to_string());
+                    metainfo.set("".to_string());
+                    format!("Added {}", torrent.name)
+                }
+                Ok(TorrentAddResult::Duplicate(torrent)) => {
+                    format!("{} has already been added", torrent.name)
+                }
+                Err(e) => e.to_string(),
+            };
+            message.set(Some(result));
+            adding.set(false);
+        });
+    };
+
+    render! {
+        div { class: "fixed inset-0 z-10 flex items-center justify-center bg-black/50",
+            div { class: "flex flex-col gap-2 p-3 w-full max-w-md bg-white dark:bg-grey-100",
+                div { class: "flex flex-row justify-between items-center",
+                    h2 { class: "font-display text-lg", "Add torrent" }
+                    button { onclick: move |_| onclose.call(()),
+                        Icon { width: 16, height: 16, icon: IoCloseOutline }
+                    }
+                }
+                label { class: "flex flex-col text-sm",
+                    "Magnet link or URL"
+                    input {
+                        value: "{magnet}",
+                        disabled: !metainfo.is_empty(),
+                        oninput: move |ev| magnet.set(ev.value.clone())
+                    }
+                }
+                label { class: "flex flex-col text-sm",
+                    "or a .torrent file"
+                    // The browser keeps a picked file in the input, so swap the input out rather
+                    // than trying to clear it.
+                    if metainfo.is_empty() {
+                        rsx! { input { id: FILE_INPUT_ID, r#type: "file", accept: ".torrent,application/x-bittorrent" } }
+                    } else {
+                        rsx! {
+                            div { class: "flex flex-row gap-2 items-center",
+                                "A .torrent file is selected"
+                                button { onclick: move |_| metainfo.set("".to_string()), "Remove file" }
+                            }
+                        }
+                    }
+                    input {
+                        id: "{FILE_INPUT_ID}-base64",
+                        r#type: "hidden",
+                        oninput: move |ev| metainfo.set(ev.value.clone())
+                    }
+                }
+                label { class: "flex flex-col text-sm",
+                    "Download directory"
+                    input {
+                        value: "{download_dir}",
+                        placeholder: "Default",
+                        oninput: move |ev| download_dir.set(ev.value.clone())
+                    }
+                }
+                label { class: "flex flex-col text-sm",
+                    "Labels"
+                    input {
+                        value: "{labels}",
+                        placeholder: "Comma separated",
+                        oninput: move |ev| labels.set(ev.value.clone())
+                    }
+                }
+                div { class: "flex flex-row flex-wrap gap-1",
+                    suggestions.into_iter().map(|label| {
+                        let typed_labels = typed_labels.clone();
+                        let value = label.clone();
+                        rsx! {
+                            button {
+                                key: "{label}",
+                                class: "px-1 text-xs bg-beige-800 dark:bg-grey-200",
+                                onclick: move |_| {
+                                    let mut new_labels = typed_labels.clone();
+                                    new_labels.push(value.clone());
+                                    labels.set(new_labels.join(", "));
+                                },
+                                "{label}"
+                            }
+                        }
+                    })
+                }
+                label { class: "flex flex-row gap-2 items-center text-sm",
+                    "Priority"
+                    select {
+                        oninput: move |ev| {
+                            priority
+                                .set(
+                                    match ev.value.as_str() {
+                                        "high" => Priority::High,
+                                        "low" => Priority::Low,
+                                        _ => Priority::Normal,
+                                    },
+                                )
+                        },
+                        option { value: "normal", selected: matches!(priority.get(), Priority::Normal), "Normal" }
+                        option { value: "high", selected: matches!(priority.get(), Priority::High), "High" }
+                        option { value: "low", selected: matches!(priority.get(), Priority::Low), "Low" }
+                    }
+                }
+                label { class: "flex flex-row gap-2 items-center text-sm",
+                    input {
+                        r#type: "checkbox",
+                        checked: "{paused}",
+                        oninput: move |ev| paused.set(ev.value == "true")
+                    }
+                    "Start paused"
+                }
+                if let Some(message) = message.get() {
+                    rsx! { div { class: "text-sm", "{message}" } }
+                }
+                button {
+                    class: "p-1 bg-beige-800 dark:bg-grey-200 disabled:opacity-50",
+                    disabled: !can_submit,
+                    onclick: submit,
+                    if *adding.get() { "Adding..." } else { "Add" }
+                }
+            }
+        }
+    }
+}
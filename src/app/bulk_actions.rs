@@ -0,0 +1,191 @@
+#![allow(non_snake_case)]
+
+use std::future::Future;
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::io_icons::IoCloseOutline;
+use dioxus_free_icons::Icon;
+
+use crate::app::labels::parse_labels;
+use crate::app::move_data::MoveDataDialog;
+use crate::app::remove_torrents::RemoveTorrentsDialog;
+use crate::app::torrent_options::BulkOptionsDialog;
+use crate::app::use_store;
+use crate::transmission::client::{TorrentAction, TorrentSetRequest, TorrentSummary};
+use crate::transmission::Result;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Dialog {
+    Options,
+    Labels,
+    Move,
+    Remove,
+}
+
+/// Actions over the selected torrents. Each one is a single RPC with every selected id.
+#[inline_props]
+pub fn BulkActionBar<'a>(
+    cx: Scope,
+    torrents: Vec<&'a TorrentSummary>,
+    onclear: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    let dialog = use_state::<Option<Dialog>>(cx, || None);
+    let running = use_state(cx, || false);
+    let error = use_state::<Option<String>>(cx, || None);
+
+    let count = torrents.len();
+
+    // Runs one RPC for the whole selection, then refreshes, or shows why it failed.
+    let run = move |rpc: Box<dyn Future<Output = Result<()>>>| {
+        let store = store.clone();
+        let running = running.to_owned();
+        let error = error.to_owned();
+        let rpc = Box::into_pin(rpc);
+        running.set(true);
+        cx.spawn(async move {
+            match rpc.await {
+                Ok(()) => {
+                    error.set(None);
+                    store.refreshed().await;
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            running.set(false);
+        });
+    };
+    let run_action = move |action: TorrentAction| {
+        let client = store.client().clone();
+        let ids: Vec<i64> = torrents.iter().map(|t| t.id as i64).collect();
+        run(Box::new(async move {
+            client.torrent_action(action, ids).await.map(|_| ())
+        }));
+    };
+    let set_labels = move |labels: Vec<String>| {
+        dialog.set(None);
+        let client = store.client().clone();
+        let ids: Vec<i64> = torrents.iter().map(|t| t.id as i64).collect();
+        let request = TorrentSetRequest::new(ids).labels(labels);
+        run(Box::new(async move {
+            client.torrent_set(request).await.map(|_| ())
+        }));
+    };
+    let remove = move |delete_local_data: bool| {
+        dialog.set(None);
+        let client = store.client().clone();
+        let ids: Vec<i64> = torrents.iter().map(|t| t.id as i64).collect();
+        run(Box::new(async move {
+            client
+                .torrent_remove(ids, delete_local_data)
+                .await
+                .map(|_| ())
+        }));
+    };
+    let disabled = *running.get();
+    let close = move |_| dialog.set(None);
+
+    render! {
+        div { class: "flex flex-row flex-wrap items-center gap-2 p-1 text-sm bg-beige-800 dark:bg-grey-200",
+            span { class: "grow", "{count} selected" }
+            button { disabled: disabled, onclick: move |_| run_action(TorrentAction::Start), "Start" }
+            button { disabled: disabled, onclick: move |_| run_action(TorrentAction::Stop), "Pause" }
+            button { disabled: disabled, onclick: move |_| run_action(TorrentAction::Verify), "Verify" }
+            button { disabled: disabled, onclick: move |_| run_action(TorrentAction::Reannounce), "Reannounce" }
+            button { onclick: move |_| dialog.set(Some(Dialog::Options)), "Options" }
+            button { disabled: disabled, onclick: move |_| dialog.set(Some(Dialog::Labels)), "Labels" }
+            button { onclick: move |_| dialog.set(Some(Dialog::Move)), "Move data" }
+            button { onclick: move |_| dialog.set(Some(Dialog::Remove)), "Remove" }
+            button { onclick: move |_| onclear.call(()), "Clear" }
+        }
+        if let Some(error) = error.get() {
+            rsx! { div { class: "px-1 text-sm text-red-100 dark:text-red-300", "{error}" } }
+        }
+        cx.render(match dialog.get() {
+            None => rsx! { "" },
+            Some(Dialog::Options) => rsx! { BulkOptionsDialog { ids: torrents.iter().map(|t| t.id as i64).collect(), onclose: close } },
+            Some(Dialog::Labels) => rsx! { BulkLabelsDialog { torrents: torrents.clone(), onsave: set_labels, onclose: close } },
+            Some(Dialog::Move) => rsx! { MoveDataDialog { torrents: torrents.clone(), onclose: close } },
+            Some(Dialog::Remove) => rsx! { RemoveTorrentsDialog { torrents: torrents.clone(), onremove: remove, onclose: close } },
+        })
+    }
+}
+
+/// Asks for the labels to give every selected torrent. Saving is left to `onsave`, which gets the
+/// parsed labels.
+#[inline_props]
+fn BulkLabelsDialog<'a>(
+    cx: Scope,
+    torrents: Vec<&'a TorrentSummary>,
+    onsave: EventHandler<'a, Vec<String>>,
+    onclose: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    // Start from the labels every selected torrent already has.
+    let labels = use_state(cx, || {
+        let mut shared = torrents
+            .first()
+            .map(|t| t.labels.clone())
+            .unwrap_or_default();
+        shared.retain(|label| {
+            torrents
+                .iter()
+                .all(|t| t.labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+        });
+        shared.join(", ")
+    });
+
+    let count = torrents.len();
+    let typed_labels = parse_labels(labels.get());
+    let suggestions: Vec<String> = store
+        .labels()
+        .into_iter()
+        .filter(|label| !typed_labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+        .collect();
+
+    render! {
+        div { class: "fixed inset-0 z-10 flex items-center justify-center bg-black/50",
+            div { class: "flex flex-col gap-2 p-3 w-full max-w-md text-sm bg-white dark:bg-grey-100",
+                div { class: "flex flex-row justify-between items-center",
+                    h2 { class: "font-display text-lg", "Labels for {count} torrents" }
+                    button { onclick: move |_| onclose.call(()),
+                        Icon { width: 16, height: 16, icon: IoCloseOutline }
+                    }
+                }
+                label { class: "flex flex-col",
+                    "Labels replace the ones each torrent has now"
+                    input {
+                        value: "{labels}",
+                        placeholder: "Comma separated",
+                        oninput: move |ev| labels.set(ev.value.clone())
+                    }
+                }
+                div { class: "flex flex-row flex-wrap gap-1",
+                    suggestions.into_iter().map(|label| {
+                        let typed_labels = typed_labels.clone();
+                        let value = label.clone();
+                        rsx! {
+                            button {
+                                key: "{label}",
+                                class: "px-1 text-xs bg-beige-800 dark:bg-grey-200",
+                                onclick: move |_| {
+                                    let mut new_labels = typed_labels.clone();
+                                    new_labels.push(value.clone());
+                                    labels.set(new_labels.join(", "));
+                                },
+                                "{label}"
+                            }
+                        }
+                    })
+                }
+                div { class: "flex flex-row justify-end gap-2",
+                    button { class: "p-1", onclick: move |_| onclose.call(()), "Cancel" }
+                    button {
+                        class: "p-1 bg-beige-800 dark:bg-grey-200",
+                        onclick: move |_| onsave.call(parse_labels(labels.get())),
+                        "Set labels"
+                    }
+                }
+            }
+        }
+    }
+}
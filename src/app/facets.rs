@@ -0,0 +1,466 @@
+#![allow(non_snake_case)]
+
+use std::collections::BTreeMap;
+
+use dioxus::prelude::*;
+use reqwest::Url;
+
+use crate::transmission::client::{TorrentStatus, TorrentSummary};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFacet {
+    Downloading,
+    Seeding,
+    Stopped,
+    Checking,
+    Errored,
+    /// Currently transferring in either direction.
+    Active,
+}
+
+impl StatusFacet {
+    pub const ALL: [StatusFacet; 6] = [
+        StatusFacet::Downloading,
+        StatusFacet::Seeding,
+        StatusFacet::Stopped,
+        StatusFacet::Checking,
+        StatusFacet::Errored,
+        StatusFacet::Active,
+    ];
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            StatusFacet::Downloading => "downloading",
+            StatusFacet::Seeding => "seeding",
+            StatusFacet::Stopped => "stopped",
+            StatusFacet::Checking => "checking",
+            StatusFacet::Errored => "errored",
+            StatusFacet::Active => "active",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusFacet::Downloading => "Downloading",
+            StatusFacet::Seeding => "Seeding",
+            StatusFacet::Stopped => "Stopped",
+            StatusFacet::Checking => "Checking",
+            StatusFacet::Errored => "Errored",
+            StatusFacet::Active => "Active",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<StatusFacet> {
+        StatusFacet::ALL
+            .into_iter()
+            .find(|facet| facet.value().eq_ignore_ascii_case(value))
+    }
+
+    pub fn matches(&self, torrent: &TorrentSummary) -> bool {
+        match self {
+            StatusFacet::Downloading => matches!(
+                torrent.status,
+                TorrentStatus::Downloading | TorrentStatus::QueuedDownload
+            ),
+            StatusFacet::Seeding => matches!(
+                torrent.status,
+                TorrentStatus::Seeding | TorrentStatus::QueuedSeed
+            ),
+            StatusFacet::Stopped => matches!(torrent.status, TorrentStatus::Stopped),
+            StatusFacet::Checking => matches!(
+                torrent.status,
+                TorrentStatus::Verifying | TorrentStatus::QueuedVerify
+            ),
+            StatusFacet::Errored => torrent.error != 0,
+            StatusFacet::Active => torrent.rate_download > 0 || torrent.rate_upload > 0,
+        }
+    }
+}
+
+/// Second-level labels that country code domains commonly register under, as in `co.uk`.
+const SECOND_LEVEL_LABELS: [&str; 10] = [
+    "ac", "co", "com", "edu", "gov", "ne", "net", "or", "org", "ltd",
+];
+
+/// The domain of an announce URL, without subdomains like `tracker.`, so that a site's
+/// trackers group together. Domains under suffixes like `co.uk` keep their own name.
+pub fn tracker_domain(announce: &str) -> Option<String> {
+    let url = Url::parse(announce).ok()?;
+    let host = url.host_str()?;
+    if url.domain().is_none() {
+        // An IP address
+        return Some(host.to_string());
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let suffix_len = match labels.as_slice() {
+        [.., second, tld] if tld.len() == 2 && SECOND_LEVEL_LABELS.contains(second) => 2,
+        _ => 1,
+    };
+    let start = labels.len().saturating_sub(suffix_len + 1);
+    Some(labels[start..].join("."))
+}
+
+/// Whether `text` names the tracker at `announce`: its domain, like `example.co.uk`, or a host
+/// under it up to the full one, like `tracker.example.co.uk`. A bare suffix like `co.uk` does not.
+pub fn tracker_matches(announce: &str, text: &str) -> bool {
+    let Some(domain) = tracker_domain(announce) else {
+        return false;
+    };
+    let host = Url::parse(announce)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+        .unwrap_or_default();
+    let under_domain = text == domain || text.ends_with(&format!(".{domain}"));
+    under_domain && (host == text || host.ends_with(&format!(".{text}")))
+}
+
+fn tracker_domains(torrent: &TorrentSummary) -> Vec<String> {
+    let mut domains: Vec<String> = torrent
+        .trackers
+        .iter()
+        .filter_map(|tracker| tracker_domain(&tracker.announce))
+        .collect();
+    domains.sort();
+    domains.dedup();
+    domains
+}
+
+/// The facet filters picked on `Home`. Each facet is either unset or one value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Facets {
+    pub status: Option<StatusFacet>,
+    pub tracker: Option<String>,
+    pub label: Option<String>,
+    pub dir: Option<String>,
+}
+
+/// Which facet to leave out when counting, so each facet's counts reflect the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Facet {
+    Status,
+    Tracker,
+    Label,
+    Dir,
+}
+
+impl Facets {
+    /// Reads facets from URL query pairs, ignoring anything unknown.
+    pub fn from_query<'a>(pairs: impl Iterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut facets = Facets::default();
+        for (key, value) in pairs {
+            let value = Some(value.to_string()).filter(|v| !v.is_empty());
+            match key {
+                "status" => facets.status = value.as_deref().and_then(StatusFacet::parse),
+                "tracker" => facets.tracker = value,
+                // Labels are compared and counted in lowercase.
+                "label" => facets.label = value.map(|label| label.to_lowercase()),
+                "dir" => facets.dir = value,
+                _ => {}
+            }
+        }
+        facets
+    }
+
+    /// The query pairs to put in the URL.
+    pub fn to_query(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        if let Some(status) = self.status {
+            pairs.push(("status", status.value().to_string()));
+        }
+        if let Some(tracker) = &self.tracker {
+            pairs.push(("tracker", tracker.clone()));
+        }
+        if let Some(label) = &self.label {
+            pairs.push(("label", label.clone()));
+        }
+        if let Some(dir) = &self.dir {
+            pairs.push(("dir", dir.clone()));
+        }
+        pairs
+    }
+
+    pub fn matches(&self, torrent: &TorrentSummary) -> bool {
+        self.matches_except(torrent, None)
+    }
+
+    fn matches_except(&self, torrent: &TorrentSummary, except: Option<Facet>) -> bool {
+        let status = except == Some(Facet::Status)
+            || self.status.is_none_or(|status| status.matches(torrent));
+        let tracker = except == Some(Facet::Tracker)
+            || self
+                .tracker
+                .as_ref()
+                .is_none_or(|domain| tracker_domains(torrent).contains(domain));
+        let label = except == Some(Facet::Label)
+            || self
+                .label
+                .as_ref()
+                .is_none_or(|label| torrent.labels.iter().any(|l| l.to_lowercase() == *label));
+        let dir = except == Some(Facet::Dir)
+            || self
+                .dir
+                .as_ref()
+                .is_none_or(|dir| &torrent.download_dir == dir);
+        status && tracker && label && dir
+    }
+}
+
+/// How many torrents each facet value would show.
+#[derive(Debug, Default)]
+struct FacetCounts {
+    status_total: usize,
+    status: Vec<(StatusFacet, usize)>,
+    tracker_total: usize,
+    label_total: usize,
+    dir_total: usize,
+    tracker: BTreeMap<String, usize>,
+    label: BTreeMap<String, usize>,
+    dir: BTreeMap<String, usize>,
+}
+
+impl FacetCounts {
+    fn count(torrents: &[&TorrentSummary], facets: &Facets) -> Self {
+        let mut counts = FacetCounts {
+            status: StatusFacet::ALL.into_iter().map(|f| (f, 0)).collect(),
+            ..Default::default()
+        };
+        for torrent in torrents {
+            if facets.matches_except(torrent, Some(Facet::Status)) {
+                counts.status_total += 1;
+                for (facet, count) in counts.status.iter_mut() {
+                    if facet.matches(torrent) {
+                        *count += 1;
+                    }
+                }
+            }
+            if facets.matches_except(torrent, Some(Facet::Tracker)) {
+                counts.tracker_total += 1;
+                for domain in tracker_domains(torrent) {
+                    *counts.tracker.entry(domain).or_default() += 1;
+                }
+            }
+            if facets.matches_except(torrent, Some(Facet::Label)) {
+                counts.label_total += 1;
+                for label in &torrent.labels {
+                    *counts.label.entry(label.to_lowercase()).or_default() += 1;
+                }
+            }
+            if facets.matches_except(torrent, Some(Facet::Dir)) {
+                counts.dir_total += 1;
+                *counts.dir.entry(torrent.download_dir.clone()).or_default() += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Facet pickers with counts. `torrents` should already be narrowed by the text filter.
+#[inline_props]
+pub fn FacetBar<'a>(
+    cx: Scope,
+    torrents: Vec<&'a TorrentSummary>,
+    facets: Facets,
+    onchange: EventHandler<'a, Facets>,
+) -> Element<'a> {
+    let counts = FacetCounts::count(torrents, facets);
+    let status = facets.status.map(|s| s.value()).unwrap_or_default();
+    let tracker = facets.tracker.clone().unwrap_or_default();
+    let label = facets.label.clone().unwrap_or_default();
+    let dir = facets.dir.clone().unwrap_or_default();
+
+    render! {
+        div { class: "flex flex-row flex-wrap gap-1 text-sm",
+            select {
+                oninput: move |ev| onchange.call(Facets {
+                    status: StatusFacet::parse(&ev.value),
+                    ..facets.clone()
+                }),
+                option { value: "", selected: status.is_empty(), "Any status ({counts.status_total})" }
+                counts.status.iter().map(|(facet, count)| {
+                    let value = facet.value();
+                    let label = facet.label();
+                    rsx! {
+                        option { key: "{value}", value: "{value}", selected: status == value, "{label} ({count})" }
+                    }
+                })
+            }
+            FacetSelect {
+                any: "Any tracker",
+                total: counts.tracker_total,
+                counts: counts.tracker,
+                value: tracker,
+                onchange: move |value| onchange.call(Facets { tracker: value, ..facets.clone() })
+            }
+            FacetSelect {
+                any: "Any label",
+                total: counts.label_total,
+                counts: counts.label,
+                value: label,
+                onchange: move |value| onchange.call(Facets { label: value, ..facets.clone() })
+            }
+            FacetSelect {
+                any: "Any directory",
+                total: counts.dir_total,
+                counts: counts.dir,
+                value: dir,
+                onchange: move |value| onchange.call(Facets { dir: value, ..facets.clone() })
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn FacetSelect<'a>(
+    cx: Scope,
+    any: &'a str,
+    total: usize,
+    counts: BTreeMap<String, usize>,
+    value: String,
+    onchange: EventHandler<'a, Option<String>>,
+) -> Element<'a> {
+    render! {
+        select {
+            oninput: move |ev| onchange.call(Some(ev.value.clone()).filter(|v| !v.is_empty())),
+            option { value: "", selected: value.is_empty(), "{any} ({total})" }
+            // Keep a picked value visible even when nothing else matches it.
+            if !value.is_empty() && !counts.contains_key(value) {
+                rsx! { option { value: "{value}", selected: true, "{value} (0)" } }
+            }
+            counts.iter().map(|(name, count)| rsx! {
+                option { key: "{name}", value: "{name}", selected: name == value, "{name} ({count})" }
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transmission::client::Tracker;
+
+    fn torrent(status: TorrentStatus, announce: &str, labels: &[&str]) -> TorrentSummary {
+        TorrentSummary {
+            id: 1.0,
+            name: "Debian".to_string(),
+            download_dir: "/downloads".to_string(),
+            percent_complete: 1.0,
+            percent_done: 1.0,
+            status,
+            size_when_done: 0.0,
+            piece_count: 0,
+            pieces: String::new(),
+            eta: -1.0,
+            peers_connected: 0,
+            peers_getting_from_us: 0,
+            peers_sending_to_us: 0,
+            rate_download: 0,
+            rate_upload: 0,
+            upload_ratio: 0.0,
+            queue_position: 0,
+            error: 0,
+            trackers: vec![Tracker {
+                id: 0,
+                announce: announce.to_string(),
+                scrape: String::new(),
+                tier: 0,
+            }],
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn tracker_domains_keep_country_suffixes() {
+        assert_eq!(
+            tracker_domain("udp://tracker.example.co.uk:6969/announce").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            tracker_domain("https://announce.example.io/announce").as_deref(),
+            Some("example.io")
+        );
+        assert_eq!(
+            tracker_domain("http://192.168.1.2:8080/announce").as_deref(),
+            Some("192.168.1.2")
+        );
+    }
+
+    #[test]
+    fn query_round_trip() {
+        let facets = Facets {
+            status: Some(StatusFacet::Seeding),
+            tracker: Some("example.org".to_string()),
+            label: Some("linux".to_string()),
+            dir: Some("/downloads".to_string()),
+        };
+        let pairs = facets.to_query();
+        let parsed = Facets::from_query(pairs.iter().map(|(k, v)| (*k, v.as_str())));
+        assert_eq!(parsed, facets);
+        assert!(Facets::default().to_query().is_empty());
+    }
+
+    #[test]
+    fn query_ignores_unknown_and_empty_values() {
+        let facets = Facets::from_query(
+            [
+                ("status", "nope"),
+                ("tracker", ""),
+                ("label", "Linux"),
+                ("q", "debian"),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(
+            facets,
+            Facets {
+                label: Some("linux".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn labels_match_and_count_ignoring_case() {
+        let a = torrent(
+            TorrentStatus::Seeding,
+            "https://tracker.example.org/a",
+            &["Linux"],
+        );
+        let b = torrent(
+            TorrentStatus::Stopped,
+            "https://tracker.example.org/a",
+            &["linux", "ISO"],
+        );
+        let facets = Facets::from_query([("label", "LINUX")].into_iter());
+        assert!(facets.matches(&a) && facets.matches(&b));
+
+        let counts = FacetCounts::count(&[&a, &b], &facets);
+        assert_eq!(counts.label_total, 2);
+        assert_eq!(counts.label.get("linux"), Some(&2));
+        assert_eq!(counts.label.get("iso"), Some(&1));
+    }
+
+    #[test]
+    fn counts_leave_out_their_own_facet() {
+        let a = torrent(TorrentStatus::Seeding, "https://tracker.example.org/a", &[]);
+        let b = torrent(TorrentStatus::Stopped, "udp://open.example.co.uk:6969", &[]);
+        let c = torrent(TorrentStatus::Seeding, "udp://open.example.co.uk:6969", &[]);
+        let facets = Facets {
+            status: Some(StatusFacet::Seeding),
+            ..Default::default()
+        };
+        let counts = FacetCounts::count(&[&a, &b, &c], &facets);
+
+        // Status counts ignore the picked status...
+        assert_eq!(counts.status_total, 3);
+        let seeding = counts
+            .status
+            .iter()
+            .find(|(f, _)| *f == StatusFacet::Seeding);
+        assert_eq!(seeding, Some(&(StatusFacet::Seeding, 2)));
+        // ...while the others only count seeding torrents.
+        assert_eq!(counts.tracker_total, 2);
+        assert_eq!(counts.tracker.get("example.org"), Some(&1));
+        assert_eq!(counts.tracker.get("example.co.uk"), Some(&1));
+        assert_eq!(counts.dir.get("/downloads"), Some(&2));
+    }
+}
@@ -0,0 +1,45 @@
+use chrono::{Local, TimeZone};
+
+/// Formats a Unix timestamp in local time. Transmission uses 0 for "never".
+pub fn format_date(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        return "-".to_string();
+    }
+    match Local.timestamp_opt(timestamp, 0).single() {
+        Some(date) => date.format("%Y-%m-%d %H:%M").to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// Formats a number of seconds as a short duration, e.g. `3h 12m`. Transmission uses negative
+/// values when the duration is unknown.
+pub fn format_duration(seconds: f64) -> String {
+    if seconds < 0.0 {
+        return "Unknown".to_string();
+    }
+    let seconds = seconds as u64;
+    let days = seconds / 86400;
+    let hours = seconds % 86400 / 3600;
+    let minutes = seconds % 3600 / 60;
+    let seconds = seconds % 60;
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+/// Formats an upload ratio. Transmission uses -1 for "not available" and -2 for infinite.
+pub fn format_ratio(ratio: f64) -> String {
+    if ratio == -2.0 {
+        "∞".to_string()
+    } else if ratio < 0.0 {
+        "None".to_string()
+    } else {
+        format!("{ratio:.2}")
+    }
+}
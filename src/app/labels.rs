@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::io_icons::{IoAddOutline, IoCloseOutline};
+use dioxus_free_icons::Icon;
+
+use crate::app::use_store;
+use crate::transmission::client::{TorrentSetRequest, TorrentSummary};
+
+/// Labels typed as a comma separated list. Transmission doesn't allow commas in labels.
+pub(crate) fn parse_labels(input: &str) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for label in input.split(',').map(str::trim) {
+        if !label.is_empty() && !labels.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            labels.push(label.to_string());
+        }
+    }
+    labels
+}
+
+/// A torrent's labels as chips, with buttons to remove them or add another.
+#[inline_props]
+pub fn TorrentLabels<'a>(cx: Scope, torrent: &'a TorrentSummary) -> Element<'a> {
+    let store = use_store(cx);
+    // Shown instead of the polled labels while a change is in flight.
+    let pending = use_state::<Option<Vec<String>>>(cx, || None);
+    let adding = use_state(cx, || false);
+    let new_label = use_state(cx, || "".to_string());
+    let error = use_state::<Option<String>>(cx, || None);
+
+    let id = torrent.id as i64;
+    let labels = pending.get().clone().unwrap_or_else(|| torrent.labels.clone());
+    let list_id = format!("rt-labels-{id}");
+
+    let set_labels = move |labels: Vec<String>| {
+        let store = store.clone();
+        let pending = pending.to_owned();
+        let error = error.to_owned();
+        pending.set(Some(labels.clone()));
+        error.set(None);
+        cx.spawn(async move {
+            let request = TorrentSetRequest::new(vec![id]).labels(labels);
+            match store.client().torrent_set(request).await {
+                Ok(_) => store.refreshed().await,
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            pending.set(None);
+        });
+    };
+
+    let add = {
+        let labels = labels.clone();
+        move |_| {
+            let mut new_labels = labels.clone();
+            new_labels.push(new_label.get().clone());
+            let new_labels = parse_labels(&new_labels.join(","));
+            if new_labels != labels {
+                set_labels(new_labels);
+            }
+            new_label.set("".to_string());
+            adding.set(false);
+        }
+    };
+
+    // Suggest labels other torrents have that this one doesn't. Only needed while adding.
+    let suggestions: Vec<String> = if *adding.get() {
+        store
+            .labels()
+            .into_iter()
+            .filter(|label| !labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    render! {
+        div { class: "flex flex-row flex-wrap items-center gap-1 px-1 text-xs",
+            labels.iter().map(|label| {
+                let remaining: Vec<String> = labels.iter().filter(|l| *l != label).cloned().collect();
+                rsx! {
+                    span { key: "{label}", class: "flex flex-row items-center gap-1 px-1 rounded bg-beige-800 dark:bg-grey-200",
+                        "{label}"
+                        button {
+                            title: "Remove label",
+                            onclick: move |_| set_labels(remaining.clone()),
+                            Icon { width: 12, height: 12, icon: IoCloseOutline }
+                        }
+                    }
+                }
+            })
+            if *adding.get() {
+                rsx! {
+                    input {
+                        class: "w-24",
+                        list: "{list_id}",
+                        value: "{new_label}",
+                        oninput: move |ev| new_label.set(ev.value.clone())
+                    }
+                    datalist { id: "{list_id}",
+                        suggestions.iter().map(|label| rsx! { option { key: "{label}", value: "{label}" } })
+                    }
+                    button { onclick: add, "Add" }
+                    button { onclick: move |_| adding.set(false), "Cancel" }
+                }
+            } else {
+                rsx! {
+                    button { title: "Add label", onclick: move |_| adding.set(true),
+                        Icon { width: 12, height: 12, icon: IoAddOutline }
+                    }
+                }
+            }
+            if let Some(error) = error.get() {
+                rsx! { span { class: "text-red-100 dark:text-red-300", "{error}" } }
+            }
+        }
+    }
+}
@@ -2,19 +2,31 @@
 
 use dioxus::prelude::*;
 use dioxus_free_icons::icons::io_icons::{
-    IoCloudDownloadOutline, IoCloudUploadOutline, IoGitNetworkOutline, IoServerOutline,
+    IoCloudDownloadOutline, IoCloudUploadOutline, IoEllipsisHorizontal, IoGitNetworkOutline,
+    IoServerOutline,
 };
 use dioxus_free_icons::Icon;
+use dioxus_router::Link;
 use human_bytes::human_bytes;
 
+use crate::app::labels::TorrentLabels;
+use crate::app::remove_torrents::RemoveTorrentsDialog;
 use crate::app::ui::{DataPoint, ProgressBar, TorrentStatusIcon};
-use crate::transmission::client::{TorrentStatus, TorrentSummary};
+use crate::app::use_store;
+use crate::transmission::client::{TorrentAction, TorrentStatus, TorrentSummary};
 
 #[inline_props]
-pub fn MiniTorrent<'a>(cx: Scope, torrent: &'a TorrentSummary) -> Element {
+pub fn MiniTorrent<'a>(cx: Scope, torrent: &'a TorrentSummary) -> Element<'a> {
+    let store = use_store(cx);
+    // Shown instead of the polled status while an action is in flight, until the store catches up.
+    let pending_status = use_state::<Option<TorrentStatus>>(cx, || None);
+    let action_error = use_state::<Option<String>>(cx, || None);
+    let confirm_remove = use_state(cx, || false);
+    let status = pending_status.get().as_ref().unwrap_or(&torrent.status);
+
     let name = torrent.name.clone();
+    let href = format!("/torrent/{}", torrent.id as i64);
     let percent = torrent.percent_complete;
-    let progress = format!("{:.2}%", 100.0 * percent);
 
     let size_completed = human_bytes(torrent.size_when_done * percent);
     let size_when_done = human_bytes(torrent.size_when_done);
@@ -22,52 +34,93 @@ pub fn MiniTorrent<'a>(cx: Scope, torrent: &'a TorrentSummary) -> Element {
     let rate_upload = human_bytes(torrent.rate_upload as f64);
     // let eta = chrono::format::strftime();
 
-    let summary = format!("{} of {} ({})", size_completed, size_when_done, progress);
-
-    let pause_text = if matches!(torrent.status, TorrentStatus::Stopped) {
-        "Resume".to_string()
+    let (pause_text, pause_action) = if matches!(status, TorrentStatus::Stopped) {
+        ("Resume".to_string(), TorrentAction::Start)
     } else {
-        "Pause".to_string()
+        ("Pause".to_string(), TorrentAction::Stop)
     };
 
+    let queued = matches!(
+        status,
+        TorrentStatus::QueuedDownload | TorrentStatus::QueuedSeed
+    );
+    let queue_position = torrent.queue_position + 1;
+
     let peers_connected = torrent.peers_connected;
     let peers_downloading = torrent.peers_sending_to_us;
     let peers_seeding = torrent.peers_getting_from_us;
 
-    // let pause_or_resume = move |_| {
-    //     let status = torrent.status.to_owned();
-    //     let id = torrent.id;
-    //     let action = if matches!(status, TorrentStatus::Stopped) {
-    //         "start".to_string()
-    //     } else {
-    //         "stop".to_string()
-    //     };
-    //
-    //     async move {
-    //         let transmission = crate::transmission::client::ClientBuilder::new()
-    //             .transmission_url("http://localhost:9091/transmission/rpc".to_string())
-    //             .build()
-    //             .unwrap();
-    //         let result = transmission.torrent_action(action, id.round() as i64).await;
-    //         println!("{:#?}", result);
-    //     }
-    // };
-    // let torrent_status = torrent_status_text(&cx.props.torrent.status);
+    let run_action = move |action: TorrentAction| {
+        let store = store.clone();
+        let pending_status = pending_status.to_owned();
+        let action_error = action_error.to_owned();
+        let ids = vec![torrent.id as i64];
+        pending_status.set(optimistic_status(action, torrent));
+        action_error.set(None);
+        cx.spawn(async move {
+            match store.client().torrent_action(action, ids).await {
+                Ok(_) => store.refreshed().await,
+                Err(e) => action_error.set(Some(e.to_string())),
+            }
+            pending_status.set(None);
+        });
+    };
+
+    let remove = move |delete_local_data: bool| {
+        let store = store.clone();
+        let action_error = action_error.to_owned();
+        let ids = vec![torrent.id as i64];
+        confirm_remove.set(false);
+        action_error.set(None);
+        cx.spawn(async move {
+            match store.client().torrent_remove(ids, delete_local_data).await {
+                Ok(_) => store.refreshed().await,
+                Err(e) => action_error.set(Some(e.to_string())),
+            }
+        });
+    };
 
     render! {
         div { class: "bg-white",
-            div { class: "p-[4px]", div { class: "font-bold flex flex-row flex-wrap items-center gap-2", "{name}" } }
+            div { class: "p-[4px] flex flex-row items-center justify-between gap-2",
+                div { class: "font-bold flex flex-row flex-wrap items-center gap-2",
+                    Link { to: "{href}", "{name}" }
+                }
+                div { class: "flex flex-row items-center gap-2 text-sm",
+                    if queued {
+                        rsx! {
+                            span { class: "whitespace-nowrap", "#{queue_position}" }
+                            button { title: "Move to top of queue", onclick: move |_| run_action(TorrentAction::QueueTop), "⤒" }
+                            button { title: "Move up in queue", onclick: move |_| run_action(TorrentAction::QueueUp), "↑" }
+                            button { title: "Move down in queue", onclick: move |_| run_action(TorrentAction::QueueDown), "↓" }
+                            button { title: "Move to bottom of queue", onclick: move |_| run_action(TorrentAction::QueueBottom), "⤓" }
+                        }
+                    }
+                    button { onclick: move |_| run_action(pause_action), "{pause_text}" }
+                    details { class: "relative",
+                        summary { class: "list-none cursor-pointer",
+                            Icon { height: 16, width: 16, icon: IoEllipsisHorizontal }
+                        }
+                        div { class: "absolute right-0 z-10 flex flex-col items-start gap-1 p-2 bg-white shadow",
+                            button { onclick: move |_| run_action(TorrentAction::StartNow), "Start now" }
+                            button { onclick: move |_| run_action(TorrentAction::Verify), "Verify" }
+                            button { onclick: move |_| run_action(TorrentAction::Reannounce), "Reannounce" }
+                            button { onclick: move |_| confirm_remove.set(true), "Remove" }
+                        }
+                    }
+                }
+            }
             ProgressBar {
-                status: &cx.props.torrent.status,
+                status: status,
                 pieces: cx.props.torrent.pieces.as_str(),
                 piece_count: cx.props.torrent.piece_count
             }
             div { class: "flex flex-row flex-wrap p-1",
                 DataPoint {
-                    icon: cx.render(rsx!(TorrentStatusIcon { status : & torrent.status })),
-                    value: torrent_status_text(&torrent.status)
+                    icon: cx.render(rsx!(TorrentStatusIcon { status : status })),
+                    value: torrent_status_text(status)
                 }
-                if let TorrentStatus::Seeding = torrent.status { None } else {
+                if let TorrentStatus::Seeding = status { None } else {
                     render!{ DataPoint {
                     icon: cx.render(rsx!(Icon { height: 16, width: 16, icon: IoCloudDownloadOutline })),
                     value: "{rate_download}/s"
@@ -80,7 +133,7 @@ pub fn MiniTorrent<'a>(cx: Scope, torrent: &'a TorrentSummary) -> Element {
                     icon: cx.render(rsx!(Icon { height : 16, width : 16, icon : IoServerOutline })),
                     value: "{size_completed} of {size_when_done}"
                 }
-                cx.render(match torrent.status {
+                cx.render(match status {
                     TorrentStatus::Seeding => rsx! {DataPoint {
                         icon: cx.render(rsx!(Icon { height: 16, width: 16, icon: IoGitNetworkOutline })),
                         value: "{peers_seeding} of {peers_connected} peers",
@@ -91,7 +144,40 @@ pub fn MiniTorrent<'a>(cx: Scope, torrent: &'a TorrentSummary) -> Element {
                     }}
                 })
             }
+            TorrentLabels { torrent: torrent }
+            if let Some(error) = action_error.get() {
+                rsx! { div { class: "px-1 text-sm text-red-100 dark:text-red-300", "{error}" } }
+            }
+            if *confirm_remove.get() {
+                rsx! {
+                    RemoveTorrentsDialog {
+                        torrents: vec![*torrent],
+                        onremove: remove,
+                        onclose: move |_| confirm_remove.set(false)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The status a torrent will most likely have once Transmission has run `action`.
+pub(crate) fn optimistic_status(
+    action: TorrentAction,
+    torrent: &TorrentSummary,
+) -> Option<TorrentStatus> {
+    match action {
+        TorrentAction::Stop => Some(TorrentStatus::Stopped),
+        TorrentAction::Start | TorrentAction::StartNow if torrent.percent_done >= 1.0 => {
+            Some(TorrentStatus::Seeding)
         }
+        TorrentAction::Start | TorrentAction::StartNow => Some(TorrentStatus::Downloading),
+        TorrentAction::Verify => Some(TorrentStatus::QueuedVerify),
+        TorrentAction::Reannounce
+        | TorrentAction::QueueTop
+        | TorrentAction::QueueUp
+        | TorrentAction::QueueDown
+        | TorrentAction::QueueBottom => None,
     }
 }
 
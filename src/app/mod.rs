@@ -1,30 +1,87 @@
 use dioxus::prelude::*;
 use dioxus_router::{Route, Router};
 use pages::Home;
+use pages::Settings;
 use pages::Torrent;
-use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
+use crate::geoip::GeoIp;
+use crate::store::{Snapshot, Store};
+use crate::user_config::UserConfig;
+
+pub mod add_torrent;
+pub mod bulk_actions;
+pub mod facets;
+pub mod format;
+pub mod labels;
 pub mod mini_torrent;
+pub mod move_data;
 pub mod pages;
+pub mod query;
+pub mod remove_torrents;
 pub mod stats_bar;
+pub mod torrent_files;
+pub mod torrent_options;
+pub mod torrent_peers;
+pub mod torrent_trackers;
 pub mod ui;
 
-#[derive(Serialize, Deserialize, Debug)]
-struct ApiResponse {
-    message: String,
-    status: String,
-}
-
 pub struct RootProps {
     pub initial_route: String,
+    pub config: UserConfig,
+    pub store: Arc<Store>,
+    pub geoip: Option<Arc<GeoIp>>,
 }
 
 #[inline_props]
-pub fn root(cx: Scope, initial_route: String) -> Element {
+pub fn root(
+    cx: Scope,
+    initial_route: String,
+    config: UserConfig,
+    store: Arc<Store>,
+    geoip: Option<Arc<GeoIp>>,
+) -> Element<'a> {
+    use_context_provider(cx, || config.clone());
+    use_context_provider(cx, || store.clone());
+    use_context_provider(cx, || geoip.clone());
     render! {
-        Router { initial_url: format!("http://10.0.0.171:3030{}", * initial_route),
+        Router { initial_url: format!("{}{}", config.public_url(), * initial_route),
             Route { to: "/", Home {} }
-            Route { to: "/torrent", Torrent {} }
+            Route { to: "/torrent/:id", Torrent {} }
+            Route { to: "/settings", Settings {} }
         }
     }
 }
+
+/// Returns the `UserConfig` provided by `root`.
+pub fn use_config(cx: &ScopeState) -> &UserConfig {
+    use_context::<UserConfig>(cx).expect("UserConfig should be provided by root")
+}
+
+/// Returns the server-wide `Store` provided by `root`.
+pub fn use_store(cx: &ScopeState) -> &Arc<Store> {
+    use_context::<Arc<Store>>(cx).expect("Store should be provided by root")
+}
+
+/// Returns the GeoIP database provided by `root`, if one is configured.
+pub fn use_geoip(cx: &ScopeState) -> Option<&Arc<GeoIp>> {
+    use_context::<Option<Arc<GeoIp>>>(cx)
+        .expect("GeoIP context should be provided by root")
+        .as_ref()
+}
+
+/// Subscribes the component to the store, re-rendering whenever a new snapshot is published.
+pub fn use_snapshot(cx: &ScopeState) -> &UseState<Snapshot> {
+    let store = use_store(cx);
+    let snapshot = use_state(cx, || store.snapshot());
+    let _updates: &Coroutine<()> = use_coroutine(cx, |_rx| {
+        let snapshot = snapshot.to_owned();
+        let mut updates = store.subscribe();
+        async move {
+            while updates.changed().await.is_ok() {
+                snapshot.set(updates.borrow().clone());
+            }
+        }
+    });
+    snapshot
+}
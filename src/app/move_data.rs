@@ -0,0 +1,164 @@
+#![allow(non_snake_case)]
+
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::io_icons::IoCloseOutline;
+use dioxus_free_icons::Icon;
+use human_bytes::human_bytes;
+
+use crate::app::{use_snapshot, use_store};
+use crate::transmission::client::TorrentSummary;
+
+/// How long to wait after the user stops typing before checking free space.
+const FREE_SPACE_DELAY: Duration = Duration::from_millis(300);
+
+/// Transmission may report a directory with or without a trailing slash.
+fn same_dir(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+#[inline_props]
+pub fn MoveDataDialog<'a>(
+    cx: Scope,
+    torrents: Vec<&'a TorrentSummary>,
+    onclose: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    let snapshot = use_snapshot(cx);
+    let location = use_state(cx, || {
+        torrents
+            .first()
+            .map(|torrent| torrent.download_dir.clone())
+            .unwrap_or_default()
+    });
+    let move_data = use_state(cx, || true);
+    let submitting = use_state(cx, || false);
+    let error = use_state::<Option<String>>(cx, || None);
+    // Where the torrents were sent, once Transmission has accepted the move.
+    let target = use_state::<Option<String>>(cx, || None);
+
+    let free_space = use_future(cx, (location.get(),), |(path,)| {
+        let store = store.clone();
+        async move {
+            tokio::time::sleep(FREE_SPACE_DELAY).await;
+            store.client().free_space(path).await
+        }
+    });
+    let free_space = match free_space.value() {
+        None => "Checking free space...".to_string(),
+        Some(Ok(space)) => format!("{} free", human_bytes(space.size_bytes)),
+        Some(Err(e)) => e.to_string(),
+    };
+
+    // Recently used directories first, then every directory a torrent is already in.
+    let mut suggestions = store.recent_dirs();
+    for torrent in snapshot.torrents.iter() {
+        if !suggestions.iter().any(|dir| same_dir(dir, &torrent.download_dir)) {
+            suggestions.push(torrent.download_dir.clone());
+        }
+    }
+
+    let count = torrents.len();
+    let size = human_bytes(torrents.iter().map(|t| t.size_when_done).sum::<f64>());
+    let moved = target
+        .get()
+        .as_ref()
+        .map(|target| {
+            torrents
+                .iter()
+                .filter(|t| same_dir(&t.download_dir, target))
+                .count()
+        })
+        .unwrap_or(0);
+    let progress = target.get().as_ref().map(|_| {
+        if moved == count {
+            format!("Moved {count} torrents")
+        } else {
+            format!("Moving... {moved} of {count} done")
+        }
+    });
+    let can_submit = !*submitting.get() && !location.trim().is_empty();
+
+    let submit = move |_| {
+        let store = store.clone();
+        let submitting = submitting.to_owned();
+        let error = error.to_owned();
+        let target = target.to_owned();
+        let ids: Vec<i64> = torrents.iter().map(|t| t.id as i64).collect();
+        let location = location.trim().to_string();
+        let move_data = *move_data.get();
+        submitting.set(true);
+        cx.spawn(async move {
+            match store
+                .client()
+                .torrent_set_location(ids, location.clone(), move_data)
+                .await
+            {
+                Ok(_) => {
+                    error.set(None);
+                    store.remember_dir(&location);
+                    target.set(Some(location));
+                    store.refresh();
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            submitting.set(false);
+        });
+    };
+
+    render! {
+        div { class: "fixed inset-0 z-10 flex items-center justify-center bg-black/50",
+            div { class: "flex flex-col gap-2 p-3 w-full max-w-md text-sm bg-white dark:bg-grey-100",
+                div { class: "flex flex-row justify-between items-center",
+                    h2 { class: "font-display text-lg", "Move data" }
+                    button { onclick: move |_| onclose.call(()),
+                        Icon { width: 16, height: 16, icon: IoCloseOutline }
+                    }
+                }
+                div { "{count} torrents, {size}" }
+                label { class: "flex flex-col",
+                    "New location"
+                    input { value: "{location}", oninput: move |ev| location.set(ev.value.clone()) }
+                }
+                div { class: "text-xs", "{free_space}" }
+                div { class: "flex flex-row flex-wrap gap-1",
+                    suggestions.into_iter().map(|dir| {
+                        let value = dir.clone();
+                        rsx! {
+                            button {
+                                key: "{dir}",
+                                class: "px-1 text-xs break-all bg-beige-800 dark:bg-grey-200",
+                                onclick: move |_| location.set(value.clone()),
+                                "{dir}"
+                            }
+                        }
+                    })
+                }
+                label { class: "flex flex-row gap-2 items-center",
+                    input {
+                        r#type: "checkbox",
+                        checked: "{move_data}",
+                        oninput: move |ev| move_data.set(ev.value == "true")
+                    }
+                    "Move the downloaded files (otherwise look for them there)"
+                }
+                if let Some(error) = error.get() {
+                    rsx! { div { class: "text-red-100 dark:text-red-300", "{error}" } }
+                }
+                if let Some(progress) = progress {
+                    rsx! { div { "{progress}" } }
+                }
+                div { class: "flex flex-row justify-end gap-2",
+                    button { class: "p-1", onclick: move |_| onclose.call(()), "Close" }
+                    button {
+                        class: "p-1 bg-beige-800 dark:bg-grey-200 disabled:opacity-50",
+                        disabled: !can_submit,
+                        onclick: submit,
+                        if *submitting.get() { "Moving..." } else { "Move" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,31 +1,33 @@
 #![allow(non_snake_case)]
 use std::cmp::Ordering;
-use std::time::Duration;
+use std::collections::BTreeSet;
+use std::rc::Rc;
 
+use dioxus::html::input_data::keyboard_types::Modifiers;
 use dioxus::prelude::*;
 use dioxus_free_icons::icons::io_icons::{
-    IoCloseCircleOutline, IoCloseOutline, IoFilterOutline, IoFunnelOutline, IoTrendingDownOutline,
-    IoTrendingUpOutline,
+    IoAddOutline, IoCloseOutline, IoFilterOutline, IoFunnelOutline, IoSettingsOutline,
 };
 use dioxus_free_icons::Icon;
-use serde::{Deserialize, Serialize};
-use tokio::time::sleep;
+use dioxus_router::{use_route, Link};
+use reqwest::Url;
 
+use crate::app::add_torrent::AddTorrentDialog;
+use crate::app::bulk_actions::BulkActionBar;
+use crate::app::facets::{FacetBar, Facets};
 use crate::app::mini_torrent::MiniTorrent;
+use crate::app::query::Query;
 use crate::app::stats_bar::StatsBar;
+use crate::app::{use_config, use_snapshot};
 use crate::transmission::client::TorrentSummary;
 
-#[derive(Serialize, Deserialize, Debug)]
-struct ApiResponse {
-    message: String,
-    status: String,
-}
-
 enum TorrentSort {
-    BySize,
-    ByName,
-    ByProgress,
-    ByStatus,
+    Size,
+    Name,
+    Progress,
+    Status,
+    Queue,
+    Label,
 }
 
 enum Order {
@@ -33,56 +35,149 @@ enum Order {
     Desc,
 }
 
+/// Id of the element holding the URL that `main.js` should show in the address bar, so filters
+/// survive a reload.
+const LOCATION_ID: &str = "rt-location";
+
+/// The browser location for the current filters, under the configured base path.
+fn home_location(base: &str, filter: &str, facets: &Facets) -> String {
+    let mut pairs = facets.to_query();
+    if !filter.is_empty() {
+        pairs.insert(0, ("q", filter.to_string()));
+    }
+    let query = Url::parse_with_params("http://localhost/", &pairs)
+        .ok()
+        .and_then(|url| url.query().map(str::to_string))
+        .unwrap_or_default();
+    if query.is_empty() {
+        base.to_string()
+    } else {
+        format!("{base}?{query}")
+    }
+}
+
+/// The labels a torrent is grouped under when sorting by label, e.g. `iso, linux`.
+fn label_group(torrent: &TorrentSummary) -> String {
+    let mut labels = torrent.labels.clone();
+    labels.sort_by_key(|label| label.to_lowercase());
+    labels.join(", ")
+}
+
 pub fn Home(cx: Scope) -> Element {
-    let torrents = use_state::<Vec<TorrentSummary>>(cx, || Vec::new());
-    let _torrents: &Coroutine<()> = use_coroutine(cx, |_rx| {
-        let torrents = torrents.to_owned();
-        let transmission = crate::transmission::client::ClientBuilder::new()
-            .transmission_url("http://localhost:9091/transmission/rpc".to_string())
-            .build()
-            .unwrap();
-        async move {
-            loop {
-                let response = transmission.torrent_summary().await.unwrap();
-                torrents.set(response.arguments.torrents);
-                sleep(Duration::from_secs(2)).await;
-            }
-        }
-    });
+    let snapshot = use_snapshot(cx);
+    let config = use_config(cx);
+    let route = use_route(cx);
+    let query: Vec<(String, String)> = route.url().query_pairs().into_owned().collect();
 
-    let torrent_filter = use_state(cx, || "".to_string());
-    let torrent_sort = use_state(cx, || TorrentSort::ByName);
+    let torrent_filter = use_state(cx, || {
+        query
+            .iter()
+            .find(|(key, _)| key == "q")
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    });
+    let facets = use_state(cx, || {
+        Facets::from_query(query.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+    });
+    let torrent_sort = use_state(cx, || TorrentSort::Name);
     let torrent_order = use_state(cx, || Order::Asc);
-    let mut torrents: Vec<_> = torrents
+    let show_add_torrent = use_state(cx, || false);
+    let selected = use_state(cx, BTreeSet::<i64>::new);
+    // The torrent last clicked without shift, where a shift-click range starts.
+    let anchor = use_state::<Option<i64>>(cx, || None);
+    // While the query has an error, show the error and don't filter.
+    let (query, query_error) = match Query::parse(torrent_filter.get()) {
+        Ok(query) => (query, None),
+        Err(e) => (Query::default(), Some(e.to_string())),
+    };
+    let text_matches: Vec<_> = snapshot
+        .torrents
         .iter()
-        .filter_map(|torrent| {
-            if torrent.name.contains(torrent_filter.as_str()) {
-                Some(torrent)
-            } else {
-                None
-            }
-        })
+        .filter(|torrent| query.matches(torrent))
+        .collect();
+    let mut torrents: Vec<_> = text_matches
+        .iter()
+        .copied()
+        .filter(|torrent| facets.matches(torrent))
         .collect();
+    let location = home_location(&config.path("/"), torrent_filter.get(), facets.get());
 
     torrents.sort_by(move |a, b| match *(torrent_sort.current()) {
-        TorrentSort::ByName => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        TorrentSort::BySize => a.size_when_done.total_cmp(&b.size_when_done),
-        TorrentSort::ByProgress => a.percent_done.total_cmp(&b.percent_done),
-        TorrentSort::ByStatus => a.status.partial_cmp(&b.status).unwrap_or(Ordering::Equal),
+        TorrentSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        TorrentSort::Size => a.size_when_done.total_cmp(&b.size_when_done),
+        TorrentSort::Progress => a.percent_done.total_cmp(&b.percent_done),
+        TorrentSort::Status => a.status.partial_cmp(&b.status).unwrap_or(Ordering::Equal),
+        TorrentSort::Queue => a.queue_position.cmp(&b.queue_position),
+        // Unlabelled torrents last
+        TorrentSort::Label => (a.labels.is_empty(), label_group(a).to_lowercase())
+            .cmp(&(b.labels.is_empty(), label_group(b).to_lowercase()))
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())),
     });
 
     if matches!(*torrent_order.current(), Order::Desc) {
         torrents.reverse();
     }
 
+    // When sorting by label, each run of torrents with the same labels gets a heading.
+    let group_by_label = matches!(*torrent_sort.current(), TorrentSort::Label);
+    let mut previous_group = None;
+    let headings: Vec<Option<String>> = torrents
+        .iter()
+        .map(|torrent| {
+            let group = label_group(torrent);
+            if !group_by_label || previous_group.as_ref() == Some(&group) {
+                return None;
+            }
+            previous_group = Some(group.clone());
+            if group.is_empty() {
+                Some("No label".to_string())
+            } else {
+                Some(group)
+            }
+        })
+        .collect();
+
+    // Torrents can be removed while selected, so only keep ids that still exist.
+    let selected_torrents: Vec<_> = snapshot
+        .torrents
+        .iter()
+        .filter(|torrent| selected.contains(&(torrent.id as i64)))
+        .collect();
+    // Shared by every row for shift-click ranges.
+    let visible_ids: Rc<Vec<i64>> = Rc::new(torrents.iter().map(|t| t.id as i64).collect());
+    let all_visible_selected =
+        !visible_ids.is_empty() && visible_ids.iter().all(|id| selected.contains(id));
+
+    let toggle_all = {
+        let visible_ids = visible_ids.clone();
+        move |_| {
+            let mut ids = selected.get().clone();
+            if all_visible_selected {
+                for id in visible_ids.iter() {
+                    ids.remove(id);
+                }
+            } else {
+                ids.extend(visible_ids.iter().copied());
+            }
+            selected.set(ids);
+        }
+    };
+
     render! {
         header { class: "fixed top-0 left-0 right-0 h-[40px] bg-beige-800 text-center font-display flex flex-row items-center justify-center text-2xl dark:bg-grey-200",
             "radio-tower"
         }
         div { class: "fixed top-[40px] left-0 right-0 h-[40px] flex flex-row gap-1 justify-between items-center p-2 bg-beige-900",
+            input {
+                r#type: "checkbox",
+                title: "Select all shown torrents",
+                checked: "{all_visible_selected}",
+                onclick: toggle_all
+            }
             Icon { width: 16, height: 16, icon: IoFunnelOutline }
             input {
                 class: "",
+                placeholder: "size>4GB status:seeding -sample",
                 value: "{torrent_filter}",
                 oninput: move |ev| torrent_filter.set(ev.value.clone())
             }
@@ -90,7 +185,7 @@ pub fn Home(cx: Scope) -> Element {
                 Icon { width: 16, height: 16, icon: IoCloseOutline }
             }
             button {
-                onclick: move |ev| {
+                onclick: move |_| {
                     torrent_order
                         .set(
                             match *(torrent_order.current()) {
@@ -110,13 +205,72 @@ pub fn Home(cx: Scope) -> Element {
                 option { value: "progress", "by progress" }
                 option { value: "size", "by size" }
                 option { value: "status", "by status" }
+                option { value: "queue", "by queue" }
+                option { value: "label", "by label" }
+            }
+            button { onclick: move |_| show_add_torrent.set(true),
+                Icon { width: 16, height: 16, icon: IoAddOutline }
+            }
+            Link { to: "/settings",
+                Icon { width: 16, height: 16, icon: IoSettingsOutline }
             }
         }
         main { class: "flex flex-col gap-2 fixed left-0 right-0 top-[80px] bottom-[44px] bg-beige-900",
-            torrents.into_iter().map(|torrent| {
+            span { id: LOCATION_ID, hidden: true, "{location}" }
+            if let Some(error) = query_error {
+                rsx! { div { class: "px-2 text-sm text-red-100 dark:text-red-300", "{error}" } }
+            }
+            FacetBar {
+                torrents: text_matches,
+                facets: facets.get().clone(),
+                onchange: move |new_facets| facets.set(new_facets)
+            }
+            if !selected_torrents.is_empty() {
+                rsx! {
+                    BulkActionBar {
+                        torrents: selected_torrents,
+                        onclear: move |_| selected.set(BTreeSet::new())
+                    }
+                }
+            }
+            torrents.into_iter().zip(headings).map(|(torrent, heading)| {
+                    let id = torrent.id as i64;
+                    let checked = selected.contains(&id);
+                    let visible_ids = visible_ids.clone();
                     render! {
-                        MiniTorrent {
-                            torrent: &torrent
+                        div { key: "{torrent.id}", class: "flex flex-col",
+                            if let Some(heading) = heading {
+                                rsx! { h3 { class: "px-2 pt-2 font-bold text-sm", "{heading}" } }
+                            }
+                            div { class: "flex flex-row items-start gap-1",
+                                input {
+                                    class: "mt-2",
+                                    r#type: "checkbox",
+                                    checked: "{checked}",
+                                    onclick: move |ev| {
+                                        let mut ids = selected.get().clone();
+                                        let range = anchor
+                                            .get()
+                                            .filter(|_| ev.modifiers().contains(Modifiers::SHIFT))
+                                            .and_then(|anchor| visible_ids.iter().position(|&i| i == anchor))
+                                            .zip(visible_ids.iter().position(|&i| i == id));
+                                        match range {
+                                            Some((from, to)) => {
+                                                let (from, to) = (from.min(to), from.max(to));
+                                                ids.extend(visible_ids[from..=to].iter().copied());
+                                            }
+                                            None => {
+                                                if !ids.remove(&id) {
+                                                    ids.insert(id);
+                                                }
+                                                anchor.set(Some(id));
+                                            }
+                                        }
+                                        selected.set(ids);
+                                    }
+                                }
+                                div { class: "grow", MiniTorrent { torrent: &torrent } }
+                            }
                         }
                     }
                 })
@@ -124,19 +278,26 @@ pub fn Home(cx: Scope) -> Element {
         footer { class: "fixed bottom-0 left-0 right-0 h-[40px] bg-beige-800 dark:bg-grey-200",
             StatsBar {}
         }
+        if *show_add_torrent.get() {
+            rsx! { AddTorrentDialog { onclose: move |_| show_add_torrent.set(false) } }
+        }
     }
 }
 
 fn parse_value(raw: String) -> TorrentSort {
     if raw == "name" {
-        TorrentSort::ByName
+        TorrentSort::Name
     } else if raw == "progress" {
-        TorrentSort::ByProgress
+        TorrentSort::Progress
     } else if raw == "status" {
-        TorrentSort::ByStatus
+        TorrentSort::Status
     } else if raw == "size" {
-        TorrentSort::BySize
+        TorrentSort::Size
+    } else if raw == "queue" {
+        TorrentSort::Queue
+    } else if raw == "label" {
+        TorrentSort::Label
     } else {
-        TorrentSort::ByName
+        TorrentSort::Name
     }
 }
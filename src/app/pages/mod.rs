@@ -1,5 +1,7 @@
 mod home;
+mod settings;
 mod torrent;
 
 pub use home::Home;
+pub use settings::Settings;
 pub use torrent::Torrent;
@@ -0,0 +1,442 @@
+#![allow(non_snake_case)]
+
+use std::collections::BTreeSet;
+
+use dioxus::prelude::*;
+use dioxus_router::Link;
+
+use crate::app::stats_bar::StatsBar;
+use crate::app::ui::{track_invalid, NumberField};
+use crate::app::use_store;
+use crate::transmission::client::{
+    BandwidthGroup, Encryption, SessionSetRequest, SessionSettings,
+};
+
+const DAYS: [(&str, i64); 7] = [
+    ("Sun", 1),
+    ("Mon", 2),
+    ("Tue", 4),
+    ("Wed", 8),
+    ("Thu", 16),
+    ("Fri", 32),
+    ("Sat", 64),
+];
+
+/// Formats minutes after midnight for an `<input type="time">`.
+fn format_time(minutes: i64) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn parse_time(value: &str) -> Option<i64> {
+    let (hours, minutes) = value.split_once(':')?;
+    let hours: i64 = hours.parse().ok()?;
+    let minutes: i64 = minutes.parse().ok()?;
+    if (0..24).contains(&hours) && (0..60).contains(&minutes) {
+        Some(hours * 60 + minutes)
+    } else {
+        None
+    }
+}
+
+pub fn Settings(cx: Scope) -> Element {
+    let store = use_store(cx);
+    // The settings as last loaded from Transmission, and the user's edits of them.
+    let original = use_state::<Option<SessionSettings>>(cx, || None);
+    let draft = use_state(cx, SessionSettings::default);
+    // Fields whose input currently does not parse, which blocks saving.
+    let invalid = use_state(cx, BTreeSet::<&'static str>::new);
+    // Bumped to remount the form, so inputs drop text the user typed.
+    let generation = use_state(cx, || 0);
+    let saving = use_state(cx, || false);
+    let message = use_state::<Option<String>>(cx, || None);
+
+    let _load = use_future(cx, (), |_| {
+        let store = store.clone();
+        let original = original.to_owned();
+        let draft = draft.to_owned();
+        let message = message.to_owned();
+        async move {
+            match store.client().session_settings().await {
+                Ok(settings) => {
+                    draft.set(settings.clone());
+                    original.set(Some(settings));
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    let Some(current) = original.get() else {
+        return render! {
+            div { class: "p-2 flex flex-col gap-2",
+                if let Some(message) = message.get() {
+                    rsx! { div { class: "text-red-100 dark:text-red-300", "{message}" } }
+                } else {
+                    rsx! { div { "Loading..." } }
+                }
+                Link { to: "/", "Back to all torrents" }
+            }
+        };
+    };
+    let changes = SessionSetRequest::changes(current, draft.get());
+    let has_changes = !changes.is_empty();
+    let can_save = !*saving.get() && invalid.is_empty() && has_changes;
+
+    let save = move |_| {
+        let store = store.clone();
+        let original = original.to_owned();
+        let draft = draft.to_owned();
+        let saving = saving.to_owned();
+        let message = message.to_owned();
+        let generation = generation.to_owned();
+        let changes = changes.clone();
+        saving.set(true);
+        cx.spawn(async move {
+            let result = match store.client().session_set(changes).await {
+                Ok(_) => store.client().session_settings().await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(settings) => {
+                    draft.set(settings.clone());
+                    original.set(Some(settings));
+                    generation.modify(|g| g + 1);
+                    message.set(Some("Saved".to_string()));
+                    store.refresh();
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+    let discard = move |_| {
+        draft.set(current.clone());
+        invalid.set(BTreeSet::new());
+        generation.modify(|g| g + 1);
+        message.set(None);
+    };
+
+    let toggle = move |apply: fn(&mut SessionSettings, bool)| {
+        move |ev: FormEvent| apply(&mut draft.make_mut(), ev.value == "true")
+    };
+    let text = move |apply: fn(&mut SessionSettings, String)| {
+        move |ev: FormEvent| apply(&mut draft.make_mut(), ev.value.clone())
+    };
+
+    let settings = draft.get();
+    let key = *generation.get();
+    // Bandwidth groups arrived in RPC version 17 (Transmission 4.0).
+    let has_groups = current.rpc_version >= 17;
+
+    render! {
+        header { class: "fixed top-0 left-0 right-0 h-[40px] bg-beige-800 text-center font-display flex flex-row items-center justify-center text-2xl dark:bg-grey-200",
+            "radio-tower"
+        }
+        main { class: "flex flex-col gap-4 fixed left-0 right-0 top-[40px] bottom-[44px] p-2 overflow-y-auto bg-beige-900 text-sm",
+            div { class: "flex flex-row justify-between items-center",
+                Link { to: "/", "Back to all torrents" }
+                span { "Transmission {settings.version}" }
+            }
+            // A key only applies to the root of a template, so the form gets a template of its own.
+            rsx! {
+                div { key: "{key}", class: "flex flex-col gap-4",
+                    Section { title: "Speed limits",
+                        Toggle { label: "Limit download speed", checked: settings.speed_limit_down_enabled, onchange: toggle(|s, v| s.speed_limit_down_enabled = v) }
+                        NumberField { label: "Download limit (kB/s)", field: "speed-limit-down", value: settings.speed_limit_down as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().speed_limit_down = v as i64 }
+                        Toggle { label: "Limit upload speed", checked: settings.speed_limit_up_enabled, onchange: toggle(|s, v| s.speed_limit_up_enabled = v) }
+                        NumberField { label: "Upload limit (kB/s)", field: "speed-limit-up", value: settings.speed_limit_up as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().speed_limit_up = v as i64 }
+                    }
+                    Section { title: "Alternative speed limits",
+                        Toggle { label: "Use alternative speed limits", checked: settings.alt_speed_enabled, onchange: toggle(|s, v| s.alt_speed_enabled = v) }
+                        NumberField { label: "Download limit (kB/s)", field: "alt-speed-down", value: settings.alt_speed_down as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().alt_speed_down = v as i64 }
+                        NumberField { label: "Upload limit (kB/s)", field: "alt-speed-up", value: settings.alt_speed_up as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().alt_speed_up = v as i64 }
+                        Toggle { label: "Turn on automatically on a schedule", checked: settings.alt_speed_time_enabled, onchange: toggle(|s, v| s.alt_speed_time_enabled = v) }
+                        TimeField { label: "From", field: "alt-speed-time-begin", value: settings.alt_speed_time_begin, invalid: invalid, onchange: move |v| draft.make_mut().alt_speed_time_begin = v }
+                        TimeField { label: "To", field: "alt-speed-time-end", value: settings.alt_speed_time_end, invalid: invalid, onchange: move |v| draft.make_mut().alt_speed_time_end = v }
+                        div { class: "flex flex-row flex-wrap gap-2",
+                            DAYS.iter().map(|&(day, bit)| {
+                                let checked = settings.alt_speed_time_day & bit != 0;
+                                rsx! {
+                                    label { key: "{day}", class: "flex flex-row gap-1 items-center",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: "{checked}",
+                                            oninput: move |ev| {
+                                                let mut settings = draft.make_mut();
+                                                if ev.value == "true" {
+                                                    settings.alt_speed_time_day |= bit;
+                                                } else {
+                                                    settings.alt_speed_time_day &= !bit;
+                                                }
+                                            }
+                                        }
+                                        "{day}"
+                                    }
+                                }
+                            })
+                        }
+                    }
+                    Section { title: "Peers",
+                        NumberField { label: "Maximum peers overall", field: "peer-limit-global", value: settings.peer_limit_global as f64, min: 1.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().peer_limit_global = v as i64 }
+                        NumberField { label: "Maximum peers per torrent", field: "peer-limit-per-torrent", value: settings.peer_limit_per_torrent as f64, min: 1.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().peer_limit_per_torrent = v as i64 }
+                        label { class: "flex flex-row gap-2 items-center",
+                            "Encryption"
+                            select {
+                                oninput: move |ev| {
+                                    draft.make_mut().encryption = match ev.value.as_str() {
+                                        "required" => Encryption::Required,
+                                        "tolerated" => Encryption::Tolerated,
+                                        _ => Encryption::Preferred,
+                                    };
+                                },
+                                option { value: "preferred", selected: settings.encryption == Encryption::Preferred, "Prefer encryption" }
+                                option { value: "required", selected: settings.encryption == Encryption::Required, "Require encryption" }
+                                option { value: "tolerated", selected: settings.encryption == Encryption::Tolerated, "Allow unencrypted" }
+                            }
+                        }
+                        Toggle { label: "Use distributed hash table (DHT)", checked: settings.dht_enabled, onchange: toggle(|s, v| s.dht_enabled = v) }
+                        Toggle { label: "Use peer exchange (PEX)", checked: settings.pex_enabled, onchange: toggle(|s, v| s.pex_enabled = v) }
+                        Toggle { label: "Use local peer discovery (LPD)", checked: settings.lpd_enabled, onchange: toggle(|s, v| s.lpd_enabled = v) }
+                        Toggle { label: "Use µTP", checked: settings.utp_enabled, onchange: toggle(|s, v| s.utp_enabled = v) }
+                    }
+                    Section { title: "Network",
+                        NumberField { label: "Peer port", field: "peer-port", value: settings.peer_port as f64, min: 1.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().peer_port = v as i64 }
+                        Toggle { label: "Pick a random port on start", checked: settings.peer_port_random_on_start, onchange: toggle(|s, v| s.peer_port_random_on_start = v) }
+                        Toggle { label: "Forward the port with UPnP or NAT-PMP", checked: settings.port_forwarding_enabled, onchange: toggle(|s, v| s.port_forwarding_enabled = v) }
+                        Toggle { label: "Use a blocklist", checked: settings.blocklist_enabled, onchange: toggle(|s, v| s.blocklist_enabled = v) }
+                        TextField { label: "Blocklist URL", value: settings.blocklist_url.clone(), onchange: text(|s, v| s.blocklist_url = v) }
+                        div { "The blocklist has {settings.blocklist_size} rules" }
+                    }
+                    Section { title: "Downloading",
+                        TextField { label: "Download directory", value: settings.download_dir.clone(), onchange: text(|s, v| s.download_dir = v) }
+                        Toggle { label: "Keep incomplete torrents in another directory", checked: settings.incomplete_dir_enabled, onchange: toggle(|s, v| s.incomplete_dir_enabled = v) }
+                        TextField { label: "Incomplete directory", value: settings.incomplete_dir.clone(), onchange: text(|s, v| s.incomplete_dir = v) }
+                        Toggle { label: "Append .part to incomplete files", checked: settings.rename_partial_files, onchange: toggle(|s, v| s.rename_partial_files = v) }
+                        Toggle { label: "Start added torrents", checked: settings.start_added_torrents, onchange: toggle(|s, v| s.start_added_torrents = v) }
+                        Toggle { label: "Delete .torrent files after adding them", checked: settings.trash_original_torrent_files, onchange: toggle(|s, v| s.trash_original_torrent_files = v) }
+                        NumberField { label: "Disk cache (MB)", field: "cache-size-mb", value: settings.cache_size_mb as f64, min: 0.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().cache_size_mb = v as i64 }
+                    }
+                    Section { title: "Queue",
+                        Toggle { label: "Limit active downloads", checked: settings.download_queue_enabled, onchange: toggle(|s, v| s.download_queue_enabled = v) }
+                        NumberField { label: "Active downloads", field: "download-queue-size", value: settings.download_queue_size as f64, min: 1.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().download_queue_size = v as i64 }
+                        Toggle { label: "Limit active seeds", checked: settings.seed_queue_enabled, onchange: toggle(|s, v| s.seed_queue_enabled = v) }
+                        NumberField { label: "Active seeds", field: "seed-queue-size", value: settings.seed_queue_size as f64, min: 1.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().seed_queue_size = v as i64 }
+                        Toggle { label: "Skip torrents that are stalled", checked: settings.queue_stalled_enabled, onchange: toggle(|s, v| s.queue_stalled_enabled = v) }
+                        NumberField { label: "Stalled after (minutes)", field: "queue-stalled-minutes", value: settings.queue_stalled_minutes as f64, min: 1.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().queue_stalled_minutes = v as i64 }
+                    }
+                    Section { title: "Seeding",
+                        Toggle { label: "Stop seeding at ratio", checked: settings.seed_ratio_limited, onchange: toggle(|s, v| s.seed_ratio_limited = v) }
+                        NumberField { label: "Ratio", field: "seedRatioLimit", value: settings.seed_ratio_limit, min: 0.0, max: 1e6, integer: false, invalid: invalid, onchange: move |v| draft.make_mut().seed_ratio_limit = v }
+                        Toggle { label: "Stop seeding when idle", checked: settings.idle_seeding_limit_enabled, onchange: toggle(|s, v| s.idle_seeding_limit_enabled = v) }
+                        NumberField { label: "Idle for (minutes)", field: "idle-seeding-limit", value: settings.idle_seeding_limit as f64, min: 1.0, max: 1e6, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().idle_seeding_limit = v as i64 }
+                    }
+                }
+            }
+            if let Some(message) = message.get() {
+                rsx! { div { "{message}" } }
+            }
+            div { class: "flex flex-row justify-end gap-2",
+                button { class: "p-1", disabled: !has_changes && invalid.is_empty(), onclick: discard, "Discard changes" }
+                button {
+                    class: "p-1 bg-beige-800 dark:bg-grey-200 disabled:opacity-50",
+                    disabled: !can_save,
+                    onclick: save,
+                    if *saving.get() { "Saving..." } else { "Save" }
+                }
+            }
+            if has_groups {
+                rsx! { BandwidthGroups {} }
+            }
+        }
+        footer { class: "fixed bottom-0 left-0 right-0 h-[40px] bg-beige-800 dark:bg-grey-200",
+            StatsBar {}
+        }
+    }
+}
+
+/// Bandwidth groups are saved one at a time with `group-set`, separately from the session.
+fn BandwidthGroups(cx: Scope) -> Element {
+    let store = use_store(cx);
+    // Bumped to load the groups again, and remount the editors, after one is saved.
+    let generation = use_state(cx, || 0);
+    let saving = use_state(cx, || false);
+    let message = use_state::<Option<String>>(cx, || None);
+    let groups = use_future(cx, (generation.get(),), |_| {
+        let store = store.clone();
+        async move { store.client().group_get(Vec::new()).await }
+    });
+
+    let save = move |group: BandwidthGroup| {
+        let store = store.clone();
+        let generation = generation.to_owned();
+        let saving = saving.to_owned();
+        let message = message.to_owned();
+        saving.set(true);
+        cx.spawn(async move {
+            match store.client().group_set(group).await {
+                Ok(_) => {
+                    message.set(None);
+                    generation.modify(|g| g + 1);
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+    let key = *generation.get();
+    let disabled = *saving.get();
+
+    render! {
+        Section { title: "Bandwidth groups",
+            cx.render(match groups.value() {
+                None => rsx! { div { "Loading..." } },
+                Some(Err(e)) => rsx! { div { class: "text-red-100 dark:text-red-300", "{e}" } },
+                Some(Ok(groups)) => rsx! {
+                    groups.iter().map(|group| rsx! {
+                        GroupEditor {
+                            key: "{key}-{group.name}",
+                            group: group.clone(),
+                            is_new: false,
+                            disabled: disabled,
+                            onsave: save
+                        }
+                    })
+                    GroupEditor {
+                        key: "{key}-new",
+                        group: BandwidthGroup { honors_session_limits: true, ..Default::default() },
+                        is_new: true,
+                        disabled: disabled,
+                        onsave: save
+                    }
+                },
+            })
+            if let Some(message) = message.get() {
+                rsx! { div { class: "text-red-100 dark:text-red-300", "{message}" } }
+            }
+        }
+    }
+}
+
+/// One bandwidth group's settings. New groups also get a name field.
+#[inline_props]
+fn GroupEditor<'a>(
+    cx: Scope,
+    group: BandwidthGroup,
+    is_new: bool,
+    disabled: bool,
+    onsave: EventHandler<'a, BandwidthGroup>,
+) -> Element<'a> {
+    let draft = use_state(cx, || group.clone());
+    let invalid = use_state(cx, BTreeSet::<&'static str>::new);
+
+    let can_save =
+        !*disabled && invalid.is_empty() && !draft.name.trim().is_empty() && draft.get() != group;
+
+    let save = move |_| {
+        onsave.call(BandwidthGroup {
+            name: draft.name.trim().to_string(),
+            ..draft.get().clone()
+        })
+    };
+    let toggle = move |apply: fn(&mut BandwidthGroup, bool)| {
+        move |ev: FormEvent| apply(&mut draft.make_mut(), ev.value == "true")
+    };
+    let edited = draft.get();
+
+    render! {
+        div { class: "flex flex-col gap-1 p-2 bg-beige-800 dark:bg-grey-200",
+            if *is_new {
+                rsx! {
+                    TextField {
+                        label: "New group name",
+                        value: edited.name.clone(),
+                        onchange: move |ev: FormEvent| draft.make_mut().name = ev.value.clone()
+                    }
+                }
+            } else {
+                rsx! { h3 { class: "font-bold", "{edited.name}" } }
+            }
+            Toggle { label: "Honor global speed limits", checked: edited.honors_session_limits, onchange: toggle(|g, v| g.honors_session_limits = v) }
+            Toggle { label: "Limit download speed", checked: edited.speed_limit_down_enabled, onchange: toggle(|g, v| g.speed_limit_down_enabled = v) }
+            NumberField { label: "Download limit (kB/s)", field: "speed-limit-down", value: edited.speed_limit_down as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().speed_limit_down = v as i64 }
+            Toggle { label: "Limit upload speed", checked: edited.speed_limit_up_enabled, onchange: toggle(|g, v| g.speed_limit_up_enabled = v) }
+            NumberField { label: "Upload limit (kB/s)", field: "speed-limit-up", value: edited.speed_limit_up as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().speed_limit_up = v as i64 }
+            div { class: "flex flex-row justify-end",
+                button {
+                    class: "p-1 bg-beige-900 dark:bg-grey-100 disabled:opacity-50",
+                    disabled: !can_save,
+                    onclick: save,
+                    if *is_new { "Create" } else { "Save" }
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn Section<'a>(cx: Scope, title: &'a str, children: Element<'a>) -> Element<'a> {
+    render! {
+        section { class: "flex flex-col gap-1",
+            h2 { class: "font-display text-lg", "{title}" }
+            children
+        }
+    }
+}
+
+#[inline_props]
+fn Toggle<'a>(
+    cx: Scope,
+    label: &'a str,
+    checked: bool,
+    onchange: EventHandler<'a, FormEvent>,
+) -> Element<'a> {
+    render! {
+        label { class: "flex flex-row gap-2 items-center",
+            input { r#type: "checkbox", checked: "{checked}", oninput: move |ev| onchange.call(ev) }
+            "{label}"
+        }
+    }
+}
+
+#[inline_props]
+fn TextField<'a>(
+    cx: Scope,
+    label: &'a str,
+    value: String,
+    onchange: EventHandler<'a, FormEvent>,
+) -> Element<'a> {
+    render! {
+        label { class: "flex flex-col",
+            "{label}"
+            input { value: "{value}", oninput: move |ev| onchange.call(ev) }
+        }
+    }
+}
+
+#[inline_props]
+fn TimeField<'a>(
+    cx: Scope,
+    label: &'a str,
+    field: &'static str,
+    value: i64,
+    invalid: &'a UseState<BTreeSet<&'static str>>,
+    onchange: EventHandler<'a, i64>,
+) -> Element<'a> {
+    let time = format_time(*value);
+    render! {
+        label { class: "flex flex-row gap-2 items-center",
+            "{label}"
+            input {
+                r#type: "time",
+                value: "{time}",
+                oninput: move |ev| {
+                    let minutes = parse_time(&ev.value);
+                    track_invalid(invalid, field, minutes.is_some());
+                    if let Some(minutes) = minutes {
+                        onchange.call(minutes);
+                    }
+                }
+            }
+        }
+    }
+}
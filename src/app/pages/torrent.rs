@@ -1,29 +1,249 @@
-use std::time::Duration;
+#![allow(non_snake_case)]
 
 use dioxus::prelude::*;
-use dioxus_router::Link;
-use serde::{Deserialize, Serialize};
-use tokio::time::sleep;
+use dioxus_router::{use_route, Link};
+use human_bytes::human_bytes;
 
+use crate::app::format::{format_date, format_duration, format_ratio};
 use crate::app::mini_torrent::MiniTorrent;
+use crate::app::move_data::MoveDataDialog;
 use crate::app::stats_bar::StatsBar;
-use crate::transmission::client::TorrentSummary;
+use crate::app::torrent_files::TorrentFiles;
+use crate::app::torrent_options::TorrentOptions;
+use crate::app::torrent_peers::TorrentPeers;
+use crate::app::torrent_trackers::TorrentTrackers;
+use crate::app::{use_snapshot, use_store};
+use crate::transmission::client::TorrentDetail;
+use crate::transmission::Error;
 
-#[derive(Serialize, Deserialize, Debug)]
-struct ApiResponse {
-    message: String,
-    status: String,
+#[derive(PartialEq)]
+enum Tab {
+    Files,
+    Peers,
+    Trackers,
+    Options,
 }
 
 pub fn Torrent(cx: Scope) -> Element {
+    let route = use_route(cx);
+    let id = route
+        .segment("id")
+        .and_then(|id| id.parse::<i64>().ok())
+        .unwrap_or(-1);
+    let store = use_store(cx);
+    let snapshot = use_snapshot(cx);
+    let tab = use_state(cx, || Tab::Files);
+    let show_move = use_state(cx, || false);
+    let rename_error = use_state::<Option<String>>(cx, || None);
+    let detail = use_state::<Option<TorrentDetail>>(cx, || None);
+    // Why the last refetch failed, if it did. The page keeps showing the last good detail.
+    let error = use_state::<Option<Error>>(cx, || None);
+
+    // Refetch whenever the shared poller publishes, so the page updates at the same cadence as
+    // the torrent list.
+    let _detail = use_future(cx, (&id,), |(id,)| {
+        let store = store.clone();
+        let detail = detail.to_owned();
+        let error = error.to_owned();
+        async move {
+            let mut updates = store.subscribe();
+            loop {
+                match store.client().torrent_detail(id).await {
+                    Ok(fetched) => {
+                        detail.set(Some(fetched));
+                        error.set(None);
+                    }
+                    Err(e) => error.set(Some(e)),
+                }
+                if updates.changed().await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let summary = snapshot.torrents.iter().find(|t| t.id as i64 == id);
+
+    // Renames are applied to the page and the store as soon as Transmission confirms them.
+    let rename = move |(path, name): (String, String)| {
+        let store = store.clone();
+        let detail = detail.to_owned();
+        let rename_error = rename_error.to_owned();
+        cx.spawn(async move {
+            match store.client().torrent_rename_path(id, path, name).await {
+                Ok(renamed) => {
+                    rename_error.set(None);
+                    detail.with_mut(|detail| {
+                        if let Some(detail) = detail {
+                            detail.apply_rename(&renamed);
+                        }
+                    });
+                    if !renamed.path.contains('/') {
+                        store.rename_torrent(id, &renamed.name);
+                    }
+                }
+                Err(e) => rename_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let body = match (detail.get(), error.get()) {
+        (None, None) => rsx! { div { class: "p-2", "Loading..." } },
+        (None, Some(e)) => rsx! {
+            div { class: "p-2 flex flex-col gap-2",
+                div { class: "text-red-100 dark:text-red-300", "{e}" }
+                Link { to: "/", "Back to all torrents" }
+            }
+        },
+        (Some(detail), error) => rsx! {
+            if let Some(error) = error {
+                rsx! { div { class: "px-2 text-sm text-red-100 dark:text-red-300", "{error}" } }
+            }
+            if let Some(summary) = summary {
+                rsx! {
+                    MiniTorrent { torrent: summary }
+                    div { class: "flex flex-row justify-end px-2 text-sm",
+                        button { onclick: move |_| show_move.set(true), "Move data" }
+                    }
+                    if *show_move.get() {
+                        rsx! {
+                            MoveDataDialog {
+                                torrents: vec![summary],
+                                onclose: move |_| show_move.set(false)
+                            }
+                        }
+                    }
+                }
+            }
+            TorrentInfo { detail: detail }
+            div { class: "flex flex-row gap-4 px-2 border-b border-beige-600",
+                TabButton { active: *tab.get() == Tab::Files, onclick: move |_| tab.set(Tab::Files), "Files" }
+                TabButton { active: *tab.get() == Tab::Peers, onclick: move |_| tab.set(Tab::Peers), "Peers" }
+                TabButton { active: *tab.get() == Tab::Trackers, onclick: move |_| tab.set(Tab::Trackers), "Trackers" }
+                TabButton { active: *tab.get() == Tab::Options, onclick: move |_| tab.set(Tab::Options), "Options" }
+            }
+            div { class: "p-2",
+                cx.render(match tab.get() {
+                    Tab::Files => rsx! {
+                        if let Some(error) = rename_error.get() {
+                            rsx! { div { class: "text-sm text-red-100 dark:text-red-300", "{error}" } }
+                        }
+                        TorrentFiles {
+                            torrent_id: id,
+                            files: &detail.files,
+                            file_stats: &detail.file_stats,
+                            onrename: rename
+                        }
+                    },
+                    Tab::Peers => rsx! {
+                        TorrentPeers {
+                            peers: &detail.peers,
+                            peers_from: &detail.peers_from,
+                            peers_connected: detail.peers_connected,
+                            peers_sending_to_us: detail.peers_sending_to_us,
+                            peers_getting_from_us: detail.peers_getting_from_us
+                        }
+                    },
+                    Tab::Trackers => rsx! {
+                        TorrentTrackers {
+                            torrent_id: id,
+                            trackers: &detail.trackers,
+                            tracker_stats: &detail.tracker_stats
+                        }
+                    },
+                    Tab::Options => rsx! { TorrentOptions { key: "{id}", torrent_id: id, limits: &detail.limits } },
+                })
+            }
+        },
+    };
+
     cx.render(rsx!(
         header { class: "sticky top-0 left-0 right-0 h-[40px] bg-beige-800 text-center font-display flex flex-row items-center justify-center text-2xl dark:bg-grey-200",
             "radio-tower"
         }
-        div { class: "flex flex-col gap-1 m-1", "Hello, world!" }
-        Link { to: "/", "Home" }
+        div { class: "px-2 py-1", Link { to: "/", "All torrents" } }
+        main { class: "flex flex-col gap-2 pb-[44px] bg-beige-900", body }
         footer { class: "fixed bottom-0 left-0 right-0 h-[40px] bg-beige-800 dark:bg-grey-200",
             StatsBar {}
         }
     ))
 }
+
+#[inline_props]
+fn TorrentInfo<'a>(cx: Scope, detail: &'a TorrentDetail) -> Element<'a> {
+    let created = match (detail.date_created, detail.creator.as_str()) {
+        (0, "") => "-".to_string(),
+        (date, "") => format_date(date),
+        (0, creator) => format!("by {creator}"),
+        (date, creator) => format!("{} by {creator}", format_date(date)),
+    };
+    let size = format!(
+        "{} ({} pieces of {})",
+        human_bytes(detail.total_size),
+        detail.piece_count,
+        human_bytes(detail.piece_size)
+    );
+    let progress = format!(
+        "{:.2}% of {}",
+        100.0 * detail.percent_done,
+        human_bytes(detail.size_when_done)
+    );
+    let transferred = format!(
+        "{} down, {} up, {} corrupt",
+        human_bytes(detail.downloaded_ever),
+        human_bytes(detail.uploaded_ever),
+        human_bytes(detail.corrupt_ever)
+    );
+    let privacy = if detail.is_private { "Private" } else { "Public" };
+
+    render! {
+        dl { class: "grid grid-cols-[max-content_1fr] gap-x-4 gap-y-1 px-2 text-sm",
+            if detail.error != 0 {
+                rsx! {
+                    dt { class: "font-bold text-red-100 dark:text-red-300", "Error" }
+                    dd { class: "break-all text-red-100 dark:text-red-300", "{detail.error_string}" }
+                }
+            }
+            InfoRow { label: "Name", value: detail.name.clone() }
+            InfoRow { label: "Hash", value: detail.hash_string.clone() }
+            InfoRow { label: "Created", value: created }
+            if !detail.comment.is_empty() {
+                rsx! { InfoRow { label: "Comment", value: detail.comment.clone() } }
+            }
+            InfoRow { label: "Location", value: detail.download_dir.clone() }
+            InfoRow { label: "Size", value: size }
+            InfoRow { label: "Progress", value: progress }
+            InfoRow { label: "Transferred", value: transferred }
+            InfoRow { label: "Ratio", value: format_ratio(detail.upload_ratio) }
+            InfoRow { label: "ETA", value: format_duration(detail.eta) }
+            InfoRow { label: "Added", value: format_date(detail.added_date) }
+            InfoRow { label: "Completed", value: format_date(detail.done_date) }
+            InfoRow { label: "Privacy", value: privacy.to_string() }
+        }
+    }
+}
+
+#[inline_props]
+fn InfoRow<'a>(cx: Scope, label: &'a str, value: String) -> Element<'a> {
+    render! {
+        dt { class: "font-bold", "{label}" }
+        dd { class: "break-all", "{value}" }
+    }
+}
+
+#[inline_props]
+fn TabButton<'a>(
+    cx: Scope,
+    active: bool,
+    onclick: EventHandler<'a, MouseEvent>,
+    children: Element<'a>,
+) -> Element<'a> {
+    let class = if *active {
+        "py-1 font-bold border-b-2 border-black dark:border-white"
+    } else {
+        "py-1"
+    };
+    render! {
+        button { class: class, onclick: move |ev| onclick.call(ev), children }
+    }
+}
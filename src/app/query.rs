@@ -0,0 +1,480 @@
+//! The search language for the torrent list, e.g.
+//! `size>4GB status:seeding label:linux ratio<1 "exact phrase" -sample`.
+//!
+//! A query is a list of terms separated by spaces, all of which must match:
+//!
+//! - `word` or `"exact phrase"` matches torrent names
+//! - `field:value` matches `name`, `label`, `tracker`, `dir` or `status`. Any other prefix is
+//!   part of a word, as in `Star Trek: TNG`
+//! - `field<value`, `field<=value`, `field>value`, `field>=value` and `field=value` compare `size`,
+//!   `ratio`, `progress` (in percent), `peers`, `down` or `up` (in bytes per second)
+//! - a leading `-` negates a term
+//!
+//! Sizes and speeds take an optional unit: `B`, `KB`, `MB`, `GB` or `TB`, all powers of 1024.
+//! Matching ignores case.
+
+use thiserror::Error;
+
+use crate::app::facets::{tracker_matches, StatusFacet};
+use crate::transmission::client::TorrentSummary;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum QueryError {
+    #[error("Missing closing quote")]
+    UnterminatedQuote,
+    #[error("Nothing to exclude after \"-\"")]
+    EmptyNegation,
+    #[error("\"{0}\" needs a value")]
+    MissingValue(String),
+    #[error("\"{field}\" can only be matched with \":\", not \"{op}\"")]
+    NotComparable { field: String, op: String },
+    #[error("\"{0}\" is not a number")]
+    InvalidNumber(String),
+    #[error("\"{0}\" is not a size, try e.g. 4GB")]
+    InvalidSize(String),
+    #[error(
+        "Unknown status \"{0}\", try downloading, seeding, stopped, checking, errored or active"
+    )]
+    UnknownStatus(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    fn compare(&self, left: f64, right: f64) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberField {
+    Size,
+    Ratio,
+    Progress,
+    Peers,
+    Down,
+    Up,
+}
+
+impl NumberField {
+    fn value(&self, torrent: &TorrentSummary) -> f64 {
+        match self {
+            NumberField::Size => torrent.size_when_done,
+            NumberField::Ratio => torrent.upload_ratio,
+            NumberField::Progress => 100.0 * torrent.percent_done,
+            NumberField::Peers => torrent.peers_connected as f64,
+            NumberField::Down => torrent.rate_download as f64,
+            NumberField::Up => torrent.rate_upload as f64,
+        }
+    }
+
+    fn parse_value(&self, value: &str) -> Result<f64, QueryError> {
+        match self {
+            NumberField::Size | NumberField::Down | NumberField::Up => parse_size(value),
+            NumberField::Progress => parse_number(value.trim_end_matches('%')),
+            NumberField::Ratio | NumberField::Peers => parse_number(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    /// Part of the name, already lowercased.
+    Name(String),
+    Label(String),
+    Tracker(String),
+    Dir(String),
+    Status(StatusFacet),
+    Compare(NumberField, Comparison, f64),
+    Not(Box<Term>),
+}
+
+impl Term {
+    fn matches(&self, torrent: &TorrentSummary) -> bool {
+        match self {
+            Term::Name(text) => torrent.name.to_lowercase().contains(text),
+            Term::Label(label) => torrent.labels.iter().any(|l| l.to_lowercase() == *label),
+            Term::Tracker(text) => torrent
+                .trackers
+                .iter()
+                .any(|tracker| tracker_matches(&tracker.announce, text)),
+            Term::Dir(text) => torrent.download_dir.to_lowercase().contains(text),
+            Term::Status(status) => status.matches(torrent),
+            Term::Compare(field, comparison, value) => {
+                comparison.compare(field.value(torrent), *value)
+            }
+            Term::Not(term) => !term.matches(torrent),
+        }
+    }
+}
+
+/// A parsed query. The empty query matches every torrent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, QueryError> {
+        let terms = tokenize(input)?
+            .into_iter()
+            .map(parse_term)
+            .collect::<Result<_, _>>()?;
+        Ok(Query { terms })
+    }
+
+    pub fn matches(&self, torrent: &TorrentSummary) -> bool {
+        self.terms.iter().all(|term| term.matches(torrent))
+    }
+}
+
+/// A query term before it is interpreted.
+#[derive(Debug, PartialEq)]
+struct Token {
+    negated: bool,
+    /// The text with quotes removed.
+    text: String,
+    /// Where the first quote was in `text`, since anything after it is literal.
+    quoted_from: Option<usize>,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            break;
+        };
+        let negated = first == '-';
+        if negated {
+            chars.next();
+        }
+
+        let mut token = Token {
+            negated,
+            text: String::new(),
+            quoted_from: None,
+        };
+        let mut in_quote = false;
+        while let Some(c) = chars.next_if(|c| in_quote || !c.is_whitespace()) {
+            if c == '"' {
+                in_quote = !in_quote;
+                token.quoted_from.get_or_insert(token.text.len());
+            } else {
+                token.text.push(c);
+            }
+        }
+        if in_quote {
+            return Err(QueryError::UnterminatedQuote);
+        }
+        if token.text.is_empty() && token.quoted_from.is_none() {
+            return Err(QueryError::EmptyNegation);
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+const OPERATORS: [(&str, Option<Comparison>); 6] = [
+    ("<=", Some(Comparison::LessOrEqual)),
+    (">=", Some(Comparison::GreaterOrEqual)),
+    ("<", Some(Comparison::Less)),
+    (">", Some(Comparison::Greater)),
+    ("=", Some(Comparison::Equal)),
+    // Text match, or equality for numbers
+    (":", None),
+];
+
+fn parse_term(token: Token) -> Result<Term, QueryError> {
+    let term = match split_field(&token) {
+        None => Term::Name(token.text.to_lowercase()),
+        Some((field, op, value)) => parse_field(field, op, value)?,
+    };
+    if token.negated {
+        Ok(Term::Not(Box::new(term)))
+    } else {
+        Ok(term)
+    }
+}
+
+/// The fields terms can name, as text fields and then number fields.
+const FIELDS: [&str; 11] = [
+    "name", "label", "tracker", "dir", "status", "size", "ratio", "progress", "peers", "down", "up",
+];
+
+/// Splits `field<op>value` at the first operator that is not inside quotes, if `field` is one of
+/// `FIELDS`.
+fn split_field(token: &Token) -> Option<(&str, &'static str, &str)> {
+    let unquoted = &token.text[..token.quoted_from.unwrap_or(token.text.len())];
+    let start = unquoted.find(['<', '>', '=', ':'])?;
+    let field = &token.text[..start];
+    if !FIELDS.contains(&field.to_lowercase().as_str()) {
+        return None;
+    }
+    let (op, _) = OPERATORS
+        .iter()
+        .find(|(op, _)| token.text[start..].starts_with(op))?;
+    let value = &token.text[start + op.len()..];
+    Some((field, op, value))
+}
+
+fn parse_field(field: &str, op: &str, value: &str) -> Result<Term, QueryError> {
+    let field_lower = field.to_lowercase();
+    let number_field = match field_lower.as_str() {
+        "size" => Some(NumberField::Size),
+        "ratio" => Some(NumberField::Ratio),
+        "progress" => Some(NumberField::Progress),
+        "peers" => Some(NumberField::Peers),
+        "down" => Some(NumberField::Down),
+        "up" => Some(NumberField::Up),
+        _ => None,
+    };
+    if value.is_empty() {
+        return Err(QueryError::MissingValue(field.to_string()));
+    }
+
+    if let Some(number_field) = number_field {
+        let comparison = OPERATORS
+            .iter()
+            .find(|(o, _)| *o == op)
+            .and_then(|(_, comparison)| *comparison)
+            .unwrap_or(Comparison::Equal);
+        let value = number_field.parse_value(value)?;
+        return Ok(Term::Compare(number_field, comparison, value));
+    }
+
+    let text = value.to_lowercase();
+    let term = match field_lower.as_str() {
+        "name" => Term::Name(text),
+        "label" => Term::Label(text),
+        "tracker" => Term::Tracker(text),
+        "dir" => Term::Dir(text),
+        "status" => Term::Status(
+            StatusFacet::parse(&text)
+                .ok_or_else(|| QueryError::UnknownStatus(value.to_string()))?,
+        ),
+        _ => unreachable!("split_field only returns known fields"),
+    };
+    if op != ":" && op != "=" {
+        return Err(QueryError::NotComparable {
+            field: field.to_string(),
+            op: op.to_string(),
+        });
+    }
+    Ok(term)
+}
+
+fn parse_number(value: &str) -> Result<f64, QueryError> {
+    value
+        .parse()
+        .map_err(|_| QueryError::InvalidNumber(value.to_string()))
+}
+
+/// Parses a size like `4GB`, `1.5 MiB` or `700`, in bytes.
+fn parse_size(value: &str) -> Result<f64, QueryError> {
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| QueryError::InvalidSize(value.to_string()))?;
+    let exponent = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        _ => return Err(QueryError::InvalidSize(value.to_string())),
+    };
+    Ok(number * 1024f64.powi(exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transmission::client::{TorrentStatus, Tracker};
+
+    fn torrent(name: &str) -> TorrentSummary {
+        TorrentSummary {
+            id: 1.0,
+            name: name.to_string(),
+            download_dir: "/downloads/linux".to_string(),
+            percent_complete: 1.0,
+            percent_done: 1.0,
+            status: TorrentStatus::Seeding,
+            size_when_done: 5.0 * 1024.0 * 1024.0 * 1024.0,
+            piece_count: 0,
+            pieces: String::new(),
+            eta: -1.0,
+            peers_connected: 3,
+            peers_getting_from_us: 1,
+            peers_sending_to_us: 0,
+            rate_download: 0,
+            rate_upload: 2048,
+            upload_ratio: 0.5,
+            queue_position: 0,
+            error: 0,
+            trackers: vec![Tracker {
+                id: 0,
+                announce: "https://tracker.example.org/announce".to_string(),
+                scrape: String::new(),
+                tier: 0,
+            }],
+            labels: vec!["Linux".to_string()],
+        }
+    }
+
+    fn matches(query: &str, torrent: &TorrentSummary) -> bool {
+        Query::parse(query).unwrap().matches(torrent)
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(Query::parse("   ").unwrap(), Query::default());
+        assert!(matches("", &torrent("anything")));
+    }
+
+    #[test]
+    fn words_match_names_ignoring_case() {
+        let t = torrent("Debian 12 Netinst");
+        assert!(matches("debian", &t));
+        assert!(matches("DEBIAN netinst", &t));
+        assert!(!matches("debian ubuntu", &t));
+    }
+
+    #[test]
+    fn phrases_keep_spaces() {
+        let t = torrent("Debian 12 Netinst");
+        assert!(matches("\"debian 12\"", &t));
+        assert!(!matches("\"debian netinst\"", &t));
+        assert_eq!(
+            Query::parse("\"a:b\"").unwrap().terms,
+            vec![Term::Name("a:b".to_string())]
+        );
+    }
+
+    #[test]
+    fn negation() {
+        let t = torrent("Debian sample");
+        assert!(!matches("-sample", &t));
+        assert!(matches("-ubuntu", &t));
+        assert!(!matches("-\"debian sample\"", &t));
+        assert_eq!(Query::parse("a -"), Err(QueryError::EmptyNegation));
+    }
+
+    #[test]
+    fn text_fields() {
+        let t = torrent("Debian");
+        assert!(matches("label:linux", &t));
+        assert!(!matches("label:bsd", &t));
+        assert!(matches("tracker:example.org", &t));
+        assert!(matches("dir:downloads", &t));
+        assert!(matches("name:deb", &t));
+        assert!(matches("status:seeding", &t));
+        assert!(!matches("status:stopped", &t));
+        assert!(matches("label:\"linux\"", &t));
+    }
+
+    #[test]
+    fn trackers_match_on_domains() {
+        let mut t = torrent("Debian");
+        t.trackers[0].announce = "udp://tracker.example.co.uk:6969/announce".to_string();
+        assert!(matches("tracker:example.co.uk", &t));
+        assert!(matches("tracker:tracker.example.co.uk", &t));
+        assert!(!matches("tracker:co.uk", &t));
+        assert!(!matches("tracker:ample.co.uk", &t));
+        assert!(!matches("tracker:announce", &t));
+    }
+
+    #[test]
+    fn number_fields() {
+        let t = torrent("Debian");
+        assert!(matches("size>4GB", &t));
+        assert!(!matches("size<4gb", &t));
+        assert!(matches("size>=5GiB", &t));
+        assert!(matches("ratio<1", &t));
+        assert!(matches("ratio=0.5", &t));
+        assert!(matches("ratio:0.5", &t));
+        assert!(matches("progress>=100%", &t));
+        assert!(matches("peers>2", &t));
+        assert!(matches("up>1KB down=0", &t));
+    }
+
+    #[test]
+    fn the_example_query() {
+        let query = "size>4GB status:seeding label:linux ratio<1 \"debian 12\" -sample";
+        assert!(matches(query, &torrent("Debian 12 DVD")));
+        assert!(!matches(query, &torrent("Debian 12 DVD sample")));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Query::parse("\"open"), Err(QueryError::UnterminatedQuote));
+        assert_eq!(
+            Query::parse("size>"),
+            Err(QueryError::MissingValue("size".to_string()))
+        );
+        assert_eq!(
+            Query::parse("size>lots"),
+            Err(QueryError::InvalidSize("lots".to_string()))
+        );
+        assert_eq!(
+            Query::parse("size>4XB"),
+            Err(QueryError::InvalidSize("4XB".to_string()))
+        );
+        assert_eq!(
+            Query::parse("ratio<high"),
+            Err(QueryError::InvalidNumber("high".to_string()))
+        );
+        assert_eq!(
+            Query::parse("status:paused"),
+            Err(QueryError::UnknownStatus("paused".to_string()))
+        );
+        assert_eq!(
+            Query::parse("label>linux"),
+            Err(QueryError::NotComparable {
+                field: "label".to_string(),
+                op: ">".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_words() {
+        let t = torrent("Star Trek: TNG S01");
+        assert_eq!(
+            Query::parse("Star Trek: TNG").unwrap().terms,
+            vec![
+                Term::Name("star".to_string()),
+                Term::Name("trek:".to_string()),
+                Term::Name("tng".to_string()),
+            ]
+        );
+        assert!(matches("Star Trek: TNG", &t));
+        assert!(matches("trek:", &t));
+        assert!(!matches("colour:red", &t));
+    }
+
+    #[test]
+    fn leading_operator_is_a_word() {
+        assert_eq!(
+            Query::parse(":)").unwrap().terms,
+            vec![Term::Name(":)".to_string())]
+        );
+    }
+}
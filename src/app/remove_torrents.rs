@@ -0,0 +1,69 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::io_icons::IoCloseOutline;
+use dioxus_free_icons::Icon;
+use human_bytes::human_bytes;
+
+use crate::transmission::client::TorrentSummary;
+
+/// How many torrent names to list before summarising the rest.
+const MAX_LISTED: usize = 5;
+
+/// Confirms removing torrents. The removal itself is left to `onremove`, which gets whether to
+/// also delete the downloaded data.
+#[inline_props]
+pub fn RemoveTorrentsDialog<'a>(
+    cx: Scope,
+    torrents: Vec<&'a TorrentSummary>,
+    onremove: EventHandler<'a, bool>,
+    onclose: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let delete_local_data = use_state(cx, || false);
+
+    let count = torrents.len();
+    let size = human_bytes(torrents.iter().map(|t| t.size_when_done).sum::<f64>());
+    let title = if count == 1 {
+        "Remove torrent?".to_string()
+    } else {
+        format!("Remove {count} torrents?")
+    };
+    let unlisted = count.saturating_sub(MAX_LISTED);
+
+    render! {
+        div { class: "fixed inset-0 z-10 flex items-center justify-center bg-black/50",
+            div { class: "flex flex-col gap-2 p-3 w-full max-w-md bg-white dark:bg-grey-100",
+                div { class: "flex flex-row justify-between items-center",
+                    h2 { class: "font-display text-lg", "{title}" }
+                    button { onclick: move |_| onclose.call(()),
+                        Icon { width: 16, height: 16, icon: IoCloseOutline }
+                    }
+                }
+                ul { class: "text-sm list-disc pl-4",
+                    torrents.iter().take(MAX_LISTED).map(|torrent| rsx! {
+                        li { key: "{torrent.id}", "{torrent.name}" }
+                    })
+                    if unlisted > 0 {
+                        rsx! { li { "and {unlisted} more" } }
+                    }
+                }
+                label { class: "flex flex-row gap-2 items-center text-sm",
+                    input {
+                        r#type: "checkbox",
+                        checked: "{delete_local_data}",
+                        oninput: move |ev| delete_local_data.set(ev.value == "true")
+                    }
+                    "Also delete downloaded data ({size})"
+                }
+                div { class: "flex flex-row justify-end gap-2",
+                    button { class: "p-1", onclick: move |_| onclose.call(()), "Cancel" }
+                    button {
+                        class: "p-1 bg-red-200 text-white disabled:opacity-50",
+                        onclick: move |_| onremove.call(*delete_local_data.get()),
+                        if *delete_local_data.get() { "Remove and delete data" } else { "Remove" }
+                    }
+                }
+            }
+        }
+    }
+}
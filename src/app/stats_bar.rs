@@ -1,33 +1,51 @@
 #![allow(non_snake_case)]
 
-use std::time::Duration;
+use std::collections::BTreeSet;
 
 use dioxus::prelude::*;
 use dioxus_free_icons::icons::io_icons::{IoArrowDown, IoArrowUp};
 use dioxus_free_icons::Icon;
 use human_bytes::human_bytes;
-use tokio::time::sleep;
 
-use crate::transmission::client::SessionStats;
+use crate::app::ui::NumberField;
+use crate::app::{use_snapshot, use_store};
+use crate::transmission::client::{SessionSetRequest, SessionSettings};
+
+/// The download and upload caps currently in force, in kB/s. Alternative limits always apply
+/// while turtle mode is on.
+fn active_limits(session: &SessionSettings) -> (Option<i64>, Option<i64>) {
+    if session.alt_speed_enabled {
+        (Some(session.alt_speed_down), Some(session.alt_speed_up))
+    } else {
+        (
+            Some(session.speed_limit_down).filter(|_| session.speed_limit_down_enabled),
+            Some(session.speed_limit_up).filter(|_| session.speed_limit_up_enabled),
+        )
+    }
+}
+
+fn format_limit(limit: Option<i64>) -> String {
+    match limit {
+        Some(limit) => format!("{limit} kB/s"),
+        None => "∞".to_string(),
+    }
+}
 
 pub fn StatsBar(cx: Scope) -> Element {
-    let stats = use_state::<Option<SessionStats>>(cx, || None);
-    let _ws: &Coroutine<()> = use_coroutine(cx, |_rx| {
-        let stats = stats.to_owned();
-        let transmission = crate::transmission::client::ClientBuilder::new()
-            .transmission_url("http://localhost:9091/transmission/rpc".to_string())
-            .build()
-            .unwrap();
-        async move {
-            loop {
-                let response = transmission.session_stats().await.unwrap();
-                stats.set(Some(response.arguments));
-                sleep(Duration::from_secs(2)).await;
-            }
-        }
-    });
+    let snapshot = use_snapshot(cx);
+    let store = use_store(cx);
+    // Shown until the next snapshot confirms the change.
+    let pending_turtle = use_state::<Option<bool>>(cx, || None);
+    let show_limits = use_state(cx, || false);
+    let turtle_error = use_state::<Option<String>>(cx, || None);
+
+    if let Some(error) = snapshot.error.as_ref() {
+        return cx.render(rsx! {
+            div { class: "text-sm text-red-100 dark:text-red-300", "{error}" }
+        });
+    }
 
-    match stats.as_ref() {
+    match snapshot.session_stats.as_ref() {
         None => cx.render(rsx! { div { "Loading..." } }),
         Some(stats) => {
             let dl_speed = human_bytes(stats.download_speed);
@@ -36,7 +54,52 @@ pub fn StatsBar(cx: Scope) -> Element {
             let dl_total = human_bytes(stats.cumulative_stats.downloaded_bytes);
             let ul_total = human_bytes(stats.cumulative_stats.uploaded_bytes);
 
+            let session = snapshot.session.as_ref();
+            let turtle = pending_turtle
+                .get()
+                .or(session.map(|session| session.alt_speed_enabled))
+                .unwrap_or(false);
+            let (limit_down, limit_up) = session.map(active_limits).unwrap_or((None, None));
+            let limit_down = format_limit(limit_down);
+            let limit_up = format_limit(limit_up);
+            let limits_session = session.filter(|_| *show_limits.get());
+            let turtle_class = if turtle {
+                "px-1 bg-blue-300 text-white"
+            } else {
+                "px-1"
+            };
+
+            let toggle_turtle = move |_| {
+                let store = store.clone();
+                let pending_turtle = pending_turtle.to_owned();
+                let turtle_error = turtle_error.to_owned();
+                let enabled = !turtle;
+                pending_turtle.set(Some(enabled));
+                cx.spawn(async move {
+                    let request = SessionSetRequest {
+                        alt_speed_enabled: Some(enabled),
+                        ..Default::default()
+                    };
+                    match store.client().session_set(request).await {
+                        Ok(_) => {
+                            turtle_error.set(None);
+                            store.refreshed().await;
+                        }
+                        Err(e) => turtle_error.set(Some(e.to_string())),
+                    }
+                    pending_turtle.set(None);
+                });
+            };
+
             cx.render(rsx! {
+                if let Some(session) = limits_session {
+                    rsx! {
+                        SpeedLimitsPanel {
+                            session: session,
+                            onclose: move |_| show_limits.set(false)
+                        }
+                    }
+                }
                 div { class: "flex flex-row justify-between text-sm",
                     div { class: "flex flex-col justify-between",
                         div { class: "flex flex-row",
@@ -58,6 +121,26 @@ pub fn StatsBar(cx: Scope) -> Element {
                             "{ul_speed}/s"
                         }
                     }
+                    div { class: "flex flex-row items-center gap-2",
+                        button {
+                            class: turtle_class,
+                            title: "Alternative speed limits",
+                            disabled: session.is_none(),
+                            onclick: toggle_turtle,
+                            "🐢"
+                        }
+                        button {
+                            class: "flex flex-col text-left",
+                            title: "Edit speed limits",
+                            disabled: session.is_none(),
+                            onclick: move |_| show_limits.set(!show_limits.get()),
+                            span { "Limit ↓ {limit_down}" }
+                            span { "Limit ↑ {limit_up}" }
+                        }
+                        if let Some(error) = turtle_error.get() {
+                            rsx! { span { class: "text-red-100 dark:text-red-300", "{error}" } }
+                        }
+                    }
                     div {
                         div { "Downloaded: {dl_total}" }
                         div { "Uploaded: {ul_total}" }
@@ -67,3 +150,79 @@ pub fn StatsBar(cx: Scope) -> Element {
         }
     }
 }
+
+/// Quick editor for the global and alternative speed caps, sending only what changed.
+#[inline_props]
+fn SpeedLimitsPanel<'a>(
+    cx: Scope,
+    session: &'a SessionSettings,
+    onclose: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    let draft = use_state(cx, || (*session).clone());
+    let invalid = use_state(cx, BTreeSet::<&'static str>::new);
+    let saving = use_state(cx, || false);
+    let message = use_state::<Option<String>>(cx, || None);
+
+    let changes = SessionSetRequest::changes(session, draft.get());
+    let can_save = !*saving.get() && invalid.is_empty() && !changes.is_empty();
+
+    let save = move |_| {
+        let store = store.clone();
+        let saving = saving.to_owned();
+        let message = message.to_owned();
+        let changes = changes.clone();
+        saving.set(true);
+        cx.spawn(async move {
+            match store.client().session_set(changes).await {
+                Ok(_) => {
+                    message.set(Some("Saved".to_string()));
+                    // Stay disabled until the session shows the new limits, so a second click
+                    // can't send them again.
+                    store.refreshed().await;
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+    let settings = draft.get();
+
+    render! {
+        div { class: "fixed bottom-[44px] right-2 z-10 flex flex-col gap-2 p-3 w-64 text-sm bg-white dark:bg-grey-100",
+            div { class: "flex flex-row justify-between items-center",
+                h2 { class: "font-display text-lg", "Speed limits" }
+                button { onclick: move |_| onclose.call(()), "Close" }
+            }
+            label { class: "flex flex-row gap-2 items-center",
+                input {
+                    r#type: "checkbox",
+                    checked: "{settings.speed_limit_down_enabled}",
+                    oninput: move |ev| draft.make_mut().speed_limit_down_enabled = ev.value == "true"
+                }
+                "Limit download"
+            }
+            NumberField { label: "Download (kB/s)", field: "speed-limit-down", value: settings.speed_limit_down as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().speed_limit_down = v as i64 }
+            label { class: "flex flex-row gap-2 items-center",
+                input {
+                    r#type: "checkbox",
+                    checked: "{settings.speed_limit_up_enabled}",
+                    oninput: move |ev| draft.make_mut().speed_limit_up_enabled = ev.value == "true"
+                }
+                "Limit upload"
+            }
+            NumberField { label: "Upload (kB/s)", field: "speed-limit-up", value: settings.speed_limit_up as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().speed_limit_up = v as i64 }
+            NumberField { label: "🐢 Download (kB/s)", field: "alt-speed-down", value: settings.alt_speed_down as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().alt_speed_down = v as i64 }
+            NumberField { label: "🐢 Upload (kB/s)", field: "alt-speed-up", value: settings.alt_speed_up as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().alt_speed_up = v as i64 }
+            if let Some(message) = message.get() {
+                rsx! { div { "{message}" } }
+            }
+            button {
+                class: "p-1 bg-beige-800 dark:bg-grey-200 disabled:opacity-50",
+                disabled: !can_save,
+                onclick: save,
+                if *saving.get() { "Saving..." } else { "Save" }
+            }
+        }
+    }
+}
@@ -0,0 +1,367 @@
+#![allow(non_snake_case)]
+
+use std::sync::Arc;
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::io_icons::{IoChevronDown, IoChevronForward, IoPencilOutline};
+use dioxus_free_icons::Icon;
+use human_bytes::human_bytes;
+
+use crate::app::use_store;
+use crate::store::Store;
+use crate::transmission::client::{FileStat, Priority, TorrentFile, TorrentSetRequest};
+
+/// A directory in the file tree, built from the `/`-separated names Transmission reports.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Dir {
+    name: String,
+    /// Path within the torrent, as `torrent-rename-path` expects it. Empty for the root.
+    path: String,
+    dirs: Vec<Dir>,
+    /// Indices into the torrent's file list.
+    files: Vec<usize>,
+}
+
+impl Dir {
+    fn build(files: &[TorrentFile]) -> Dir {
+        let mut root = Dir::default();
+        for (index, file) in files.iter().enumerate() {
+            let mut dir = &mut root;
+            let mut parts: Vec<&str> = file.name.split('/').collect();
+            parts.pop();
+            for part in parts {
+                let position = match dir.dirs.iter().position(|d| d.name == part) {
+                    Some(position) => position,
+                    None => {
+                        let path = if dir.path.is_empty() {
+                            part.to_string()
+                        } else {
+                            format!("{}/{part}", dir.path)
+                        };
+                        dir.dirs.push(Dir {
+                            name: part.to_string(),
+                            path,
+                            ..Default::default()
+                        });
+                        dir.dirs.len() - 1
+                    }
+                };
+                dir = &mut dir.dirs[position];
+            }
+            dir.files.push(index);
+        }
+        root
+    }
+
+    /// Indices of every file in this directory and below.
+    fn indices(&self) -> Vec<usize> {
+        let mut indices = self.files.clone();
+        for dir in &self.dirs {
+            indices.extend(dir.indices());
+        }
+        indices
+    }
+}
+
+/// Sends a `torrent-set` and refreshes, or reports the failure in `error`.
+fn apply(
+    cx: &ScopeState,
+    store: &Arc<Store>,
+    error: &UseState<Option<String>>,
+    request: TorrentSetRequest,
+) {
+    let store = store.clone();
+    let error = error.to_owned();
+    cx.spawn(async move {
+        match store.client().torrent_set(request).await {
+            Ok(_) => {
+                error.set(None);
+                store.refresh();
+            }
+            Err(e) => error.set(Some(e.to_string())),
+        }
+    });
+}
+
+/// Reads a priority `<option>` value; anything else, like "mixed", is `None`.
+pub(crate) fn parse_priority(value: &str) -> Option<Priority> {
+    match value {
+        "high" => Some(Priority::High),
+        "normal" => Some(Priority::Normal),
+        "low" => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+fn priority_value(priority: Option<Priority>) -> &'static str {
+    match priority {
+        Some(Priority::High) => "high",
+        Some(Priority::Normal) => "normal",
+        Some(Priority::Low) => "low",
+        None => "mixed",
+    }
+}
+
+/// The torrent's files as a tree. `onrename` is called with the path to rename and its new name.
+#[inline_props]
+pub fn TorrentFiles<'a>(
+    cx: Scope,
+    torrent_id: i64,
+    files: &'a Vec<TorrentFile>,
+    file_stats: &'a Vec<FileStat>,
+    onrename: EventHandler<'a, (String, String)>,
+) -> Element<'a> {
+    let error = use_state::<Option<String>>(cx, || None);
+    let tree = Dir::build(files);
+
+    render! {
+        if let Some(error) = error.get() {
+            rsx! { div { class: "text-sm text-red-100 dark:text-red-300", "{error}" } }
+        }
+        DirNode {
+            torrent_id: *torrent_id,
+            dir: tree,
+            files: files,
+            file_stats: file_stats,
+            depth: 0,
+            error: error,
+            onrename: move |rename| onrename.call(rename)
+        }
+    }
+}
+
+#[inline_props]
+fn DirNode<'a>(
+    cx: Scope,
+    torrent_id: i64,
+    dir: Dir,
+    files: &'a Vec<TorrentFile>,
+    file_stats: &'a Vec<FileStat>,
+    depth: usize,
+    error: &'a UseState<Option<String>>,
+    onrename: EventHandler<'a, (String, String)>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    // The root has no name of its own and is always expanded.
+    let expanded = use_state(cx, || *depth <= 1);
+    let indices = dir.indices();
+
+    let length: f64 = indices.iter().map(|&i| files[i].length).sum();
+    let completed: f64 = indices.iter().map(|&i| files[i].bytes_completed).sum();
+    let progress = if length > 0.0 { completed / length } else { 1.0 };
+    let size = human_bytes(length);
+    let all_wanted = indices.iter().all(|&i| file_stats[i].wanted);
+    let priority = indices
+        .first()
+        .map(|&i| file_stats[i].priority)
+        .filter(|p| indices.iter().all(|&i| file_stats[i].priority == *p));
+
+    let toggle_wanted = {
+        let indices = indices.clone();
+        move |_| {
+            let request = TorrentSetRequest::new(vec![*torrent_id]);
+            let request = if all_wanted {
+                request.files_unwanted(indices.clone())
+            } else {
+                request.files_wanted(indices.clone())
+            };
+            apply(cx, store, error, request);
+        }
+    };
+    let set_priority = move |ev: FormEvent| {
+        if let Some(priority) = parse_priority(&ev.value) {
+            let request =
+                TorrentSetRequest::new(vec![*torrent_id]).file_priority(priority, indices.clone());
+            apply(cx, store, error, request);
+        }
+    };
+
+    let children = rsx! {
+        dir.dirs.iter().map(|child| rsx! {
+            DirNode {
+                key: "{child.name}",
+                torrent_id: *torrent_id,
+                dir: child.clone(),
+                files: files,
+                file_stats: file_stats,
+                depth: depth + 1,
+                error: error,
+                onrename: move |rename| onrename.call(rename)
+            }
+        })
+        dir.files.iter().map(|&index| rsx! {
+            FileRow {
+                key: "{index}",
+                torrent_id: *torrent_id,
+                index: index,
+                file: &files[index],
+                stat: &file_stats[index],
+                error: error,
+                onrename: move |rename| onrename.call(rename)
+            }
+        })
+    };
+
+    if *depth == 0 {
+        return render! { ul { class: "flex flex-col gap-1 text-sm", children } };
+    }
+
+    render! {
+        li { class: "flex flex-col",
+            div { class: "flex flex-row items-center gap-2",
+                button { onclick: move |_| expanded.set(!expanded.get()),
+                    if *expanded.get() {
+                        rsx! { Icon { width: 16, height: 16, icon: IoChevronDown } }
+                    } else {
+                        rsx! { Icon { width: 16, height: 16, icon: IoChevronForward } }
+                    }
+                }
+                input { r#type: "checkbox", checked: "{all_wanted}", oninput: toggle_wanted }
+                RenameName {
+                    class: "font-bold",
+                    path: dir.path.clone(),
+                    name: dir.name.clone(),
+                    onrename: move |rename| onrename.call(rename)
+                }
+                PriorityPicker { value: priority, onchange: set_priority }
+                span { class: "whitespace-nowrap", "{size}" }
+            }
+            FileProgress { progress: progress }
+            if *expanded.get() {
+                rsx! { ul { class: "flex flex-col gap-1 pl-4", children } }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn FileRow<'a>(
+    cx: Scope,
+    torrent_id: i64,
+    index: usize,
+    file: &'a TorrentFile,
+    stat: &'a FileStat,
+    error: &'a UseState<Option<String>>,
+    onrename: EventHandler<'a, (String, String)>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    let name = file.name.rsplit('/').next().unwrap_or(&file.name);
+    let progress = if file.length > 0.0 {
+        file.bytes_completed / file.length
+    } else {
+        1.0
+    };
+    let size = human_bytes(file.length);
+
+    let toggle_wanted = move |_| {
+        let request = TorrentSetRequest::new(vec![*torrent_id]);
+        let request = if stat.wanted {
+            request.files_unwanted(vec![*index])
+        } else {
+            request.files_wanted(vec![*index])
+        };
+        apply(cx, store, error, request);
+    };
+    let set_priority = move |ev: FormEvent| {
+        if let Some(priority) = parse_priority(&ev.value) {
+            let request =
+                TorrentSetRequest::new(vec![*torrent_id]).file_priority(priority, vec![*index]);
+            apply(cx, store, error, request);
+        }
+    };
+
+    render! {
+        li { class: "flex flex-col",
+            div { class: "flex flex-row items-center gap-2",
+                input { r#type: "checkbox", checked: "{stat.wanted}", oninput: toggle_wanted }
+                RenameName {
+                    class: "",
+                    path: file.name.clone(),
+                    name: name.to_string(),
+                    onrename: move |rename| onrename.call(rename)
+                }
+                PriorityPicker { value: Some(stat.priority), onchange: set_priority }
+                span { class: "whitespace-nowrap", "{size}" }
+            }
+            FileProgress { progress: progress }
+        }
+    }
+}
+
+/// A file or folder name that turns into a text input for renaming it.
+#[inline_props]
+fn RenameName<'a>(
+    cx: Scope,
+    class: &'a str,
+    path: String,
+    name: String,
+    onrename: EventHandler<'a, (String, String)>,
+) -> Element<'a> {
+    let editing = use_state(cx, || false);
+    let new_name = use_state(cx, || name.clone());
+
+    let submit = move |_| {
+        let new_name = new_name.trim();
+        if !new_name.is_empty() && new_name != name && !new_name.contains('/') {
+            onrename.call((path.clone(), new_name.to_string()));
+        }
+        editing.set(false);
+    };
+
+    if *editing.get() {
+        return render! {
+            div { class: "flex flex-row gap-1 grow",
+                input {
+                    class: "grow",
+                    value: "{new_name}",
+                    oninput: move |ev| new_name.set(ev.value.clone())
+                }
+                button { onclick: submit, "Rename" }
+                button { onclick: move |_| editing.set(false), "Cancel" }
+            }
+        };
+    }
+
+    render! {
+        span { class: "break-all grow {class}", "{name}" }
+        button {
+            title: "Rename",
+            onclick: move |_| {
+                new_name.set(name.clone());
+                editing.set(true);
+            },
+            Icon { width: 14, height: 14, icon: IoPencilOutline }
+        }
+    }
+}
+
+#[inline_props]
+fn PriorityPicker<'a>(
+    cx: Scope,
+    #[props(!optional)]
+    value: Option<Priority>,
+    onchange: EventHandler<'a, FormEvent>,
+) -> Element<'a> {
+    let value = priority_value(*value);
+    render! {
+        select { oninput: move |ev| onchange.call(ev),
+            if value == "mixed" {
+                rsx! { option { value: "mixed", selected: true, "Mixed" } }
+            }
+            option { value: "high", selected: value == "high", "High" }
+            option { value: "normal", selected: value == "normal", "Normal" }
+            option { value: "low", selected: value == "low", "Low" }
+        }
+    }
+}
+
+// `inline_props` declares the `'a` lifetime.
+#[inline_props]
+fn FileProgress(cx: Scope, progress: f64) -> Element<'a> {
+    let width = format!("{:.1}%", 100.0 * progress);
+    render! {
+        div { class: "w-full h-1 bg-beige-800 dark:bg-grey-200",
+            div { class: "h-full bg-blue-300", style: "width: {width};" }
+        }
+    }
+}
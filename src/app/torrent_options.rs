@@ -0,0 +1,383 @@
+#![allow(non_snake_case)]
+
+use std::collections::BTreeSet;
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::io_icons::IoCloseOutline;
+use dioxus_free_icons::Icon;
+
+use crate::app::torrent_files::parse_priority;
+use crate::app::ui::NumberField;
+use crate::app::use_store;
+use crate::transmission::client::{Priority, SeedLimitMode, TorrentLimits, TorrentSetRequest};
+
+fn parse_mode(value: &str) -> Option<SeedLimitMode> {
+    match value {
+        "global" => Some(SeedLimitMode::Global),
+        "single" => Some(SeedLimitMode::Single),
+        "unlimited" => Some(SeedLimitMode::Unlimited),
+        _ => None,
+    }
+}
+
+/// Reads a `GroupPicker` option: `None` for unchanged, an empty name for no group.
+fn parse_group(value: &str) -> Option<String> {
+    match value {
+        "none" => Some("".to_string()),
+        _ => value.strip_prefix("group:").map(str::to_string),
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// The "Options" tab of the detail page.
+#[inline_props]
+pub fn TorrentOptions<'a>(cx: Scope, torrent_id: i64, limits: &'a TorrentLimits) -> Element<'a> {
+    let store = use_store(cx);
+    // The limits the draft started from. Polls keep updating `limits`, but only the user's own
+    // edits should be sent, so changes are worked out against these.
+    let original = use_state(cx, || (*limits).clone());
+    let draft = use_state(cx, || (*limits).clone());
+    let invalid = use_state(cx, BTreeSet::<&'static str>::new);
+    let saving = use_state(cx, || false);
+    let message = use_state::<Option<String>>(cx, || None);
+
+    let request = TorrentSetRequest::new(vec![*torrent_id]).limits(original.get(), draft.get());
+    let can_save = !*saving.get() && invalid.is_empty() && !request.is_empty();
+
+    let save = move |_| {
+        let store = store.clone();
+        let original = original.to_owned();
+        let saving = saving.to_owned();
+        let message = message.to_owned();
+        let request = request.clone();
+        let saved = draft.get().clone();
+        saving.set(true);
+        cx.spawn(async move {
+            match store.client().torrent_set(request).await {
+                Ok(_) => {
+                    original.set(saved);
+                    message.set(Some("Saved".to_string()));
+                    store.refresh();
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+    let toggle = move |apply: fn(&mut TorrentLimits, bool)| {
+        move |ev: FormEvent| apply(&mut draft.make_mut(), ev.value == "true")
+    };
+    let options = draft.get();
+
+    render! {
+        div { class: "flex flex-col gap-2 text-sm",
+            Checkbox { label: "Limit download speed", checked: options.download_limited, onchange: toggle(|l, v| l.download_limited = v) }
+            NumberField { label: "Download limit (kB/s)", field: "downloadLimit", value: options.download_limit as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().download_limit = v as i64 }
+            Checkbox { label: "Limit upload speed", checked: options.upload_limited, onchange: toggle(|l, v| l.upload_limited = v) }
+            NumberField { label: "Upload limit (kB/s)", field: "uploadLimit", value: options.upload_limit as f64, min: 0.0, max: 1e9, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().upload_limit = v as i64 }
+            Checkbox { label: "Honor global speed limits", checked: options.honors_session_limits, onchange: toggle(|l, v| l.honors_session_limits = v) }
+            label { class: "flex flex-row gap-2 items-center",
+                "Bandwidth priority"
+                select {
+                    oninput: move |ev| {
+                        if let Some(priority) = parse_priority(&ev.value) {
+                            draft.make_mut().bandwidth_priority = priority;
+                        }
+                    },
+                    option { value: "high", selected: options.bandwidth_priority == Priority::High, "High" }
+                    option { value: "normal", selected: options.bandwidth_priority == Priority::Normal, "Normal" }
+                    option { value: "low", selected: options.bandwidth_priority == Priority::Low, "Low" }
+                }
+            }
+            ModePicker {
+                label: "Seed ratio",
+                value: options.seed_ratio_mode,
+                onchange: move |mode| draft.make_mut().seed_ratio_mode = mode
+            }
+            NumberField { label: "Stop at ratio", field: "seedRatioLimit", value: options.seed_ratio_limit, min: 0.0, max: 1e6, integer: false, invalid: invalid, onchange: move |v| draft.make_mut().seed_ratio_limit = v }
+            ModePicker {
+                label: "Idle seeding",
+                value: options.seed_idle_mode,
+                onchange: move |mode| draft.make_mut().seed_idle_mode = mode
+            }
+            NumberField { label: "Stop when idle for (minutes)", field: "seedIdleLimit", value: options.seed_idle_limit as f64, min: 1.0, max: 1e6, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().seed_idle_limit = v as i64 }
+            NumberField { label: "Maximum peers", field: "peer-limit", value: options.peer_limit as f64, min: 1.0, max: 65535.0, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().peer_limit = v as i64 }
+            NumberField { label: "Queue position", field: "queuePosition", value: options.queue_position as f64, min: 0.0, max: 1e6, integer: true, invalid: invalid, onchange: move |v| draft.make_mut().queue_position = v as i64 }
+            GroupPicker {
+                value: Some(options.group.clone()),
+                onchange: move |group: Option<String>| draft.make_mut().group = group.unwrap_or_default()
+            }
+            if let Some(message) = message.get() {
+                rsx! { div { "{message}" } }
+            }
+            div { class: "flex flex-row justify-end",
+                button {
+                    class: "p-1 bg-beige-800 dark:bg-grey-200 disabled:opacity-50",
+                    disabled: !can_save,
+                    onclick: save,
+                    if *saving.get() { "Saving..." } else { "Save" }
+                }
+            }
+        }
+    }
+}
+
+/// Sets limits on several torrents at once. Every option starts as "unchanged" and only the ones
+/// the user picks are sent.
+#[inline_props]
+pub fn BulkOptionsDialog<'a>(
+    cx: Scope,
+    ids: Vec<i64>,
+    onclose: EventHandler<'a, ()>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    let request = use_state(cx, || TorrentSetRequest::new(ids.clone()));
+    let invalid = use_state(cx, BTreeSet::<&'static str>::new);
+    let saving = use_state(cx, || false);
+    let message = use_state::<Option<String>>(cx, || None);
+
+    let count = ids.len();
+    let can_save = !*saving.get() && invalid.is_empty() && !request.is_empty();
+
+    let save = move |_| {
+        let store = store.clone();
+        let saving = saving.to_owned();
+        let message = message.to_owned();
+        let request = TorrentSetRequest {
+            ids: ids.clone(),
+            ..request.get().clone()
+        };
+        saving.set(true);
+        cx.spawn(async move {
+            match store.client().torrent_set(request).await {
+                Ok(_) => {
+                    message.set(Some("Saved".to_string()));
+                    store.refresh();
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+    let toggle = move |apply: fn(&mut TorrentSetRequest, Option<bool>)| {
+        move |ev: FormEvent| apply(&mut request.make_mut(), parse_bool(&ev.value))
+    };
+
+    render! {
+        div { class: "fixed inset-0 z-10 flex items-center justify-center bg-black/50",
+            div { class: "flex flex-col gap-2 p-3 w-full max-w-md max-h-full overflow-y-auto text-sm bg-white dark:bg-grey-100",
+                div { class: "flex flex-row justify-between items-center",
+                    h2 { class: "font-display text-lg", "Options for {count} torrents" }
+                    button { onclick: move |_| onclose.call(()),
+                        Icon { width: 16, height: 16, icon: IoCloseOutline }
+                    }
+                }
+                BoolPicker { label: "Limit download speed", onchange: toggle(|r, v| r.download_limited = v) }
+                NumberField {
+                    label: "Download limit (kB/s)",
+                    field: "downloadLimit",
+                    min: 0.0,
+                    max: 1e9,
+                    integer: true,
+                    placeholder: "Unchanged",
+                    invalid: invalid,
+                    onchange: move |v| request.make_mut().download_limit = Some(v as i64),
+                    onclear: move |_| request.make_mut().download_limit = None
+                }
+                BoolPicker { label: "Limit upload speed", onchange: toggle(|r, v| r.upload_limited = v) }
+                NumberField {
+                    label: "Upload limit (kB/s)",
+                    field: "uploadLimit",
+                    min: 0.0,
+                    max: 1e9,
+                    integer: true,
+                    placeholder: "Unchanged",
+                    invalid: invalid,
+                    onchange: move |v| request.make_mut().upload_limit = Some(v as i64),
+                    onclear: move |_| request.make_mut().upload_limit = None
+                }
+                BoolPicker { label: "Honor global speed limits", onchange: toggle(|r, v| r.honors_session_limits = v) }
+                label { class: "flex flex-row gap-2 items-center",
+                    "Bandwidth priority"
+                    select { oninput: move |ev| request.make_mut().bandwidth_priority = parse_priority(&ev.value),
+                        option { value: "", "Unchanged" }
+                        option { value: "high", "High" }
+                        option { value: "normal", "Normal" }
+                        option { value: "low", "Low" }
+                    }
+                }
+                label { class: "flex flex-row gap-2 items-center",
+                    "Seed ratio"
+                    select { oninput: move |ev| request.make_mut().seed_ratio_mode = parse_mode(&ev.value),
+                        option { value: "", "Unchanged" }
+                        option { value: "global", "Use global setting" }
+                        option { value: "single", "Stop at ratio" }
+                        option { value: "unlimited", "Seed regardless of ratio" }
+                    }
+                }
+                NumberField {
+                    label: "Stop at ratio",
+                    field: "seedRatioLimit",
+                    min: 0.0,
+                    max: 1e6,
+                    integer: false,
+                    placeholder: "Unchanged",
+                    invalid: invalid,
+                    onchange: move |v| request.make_mut().seed_ratio_limit = Some(v),
+                    onclear: move |_| request.make_mut().seed_ratio_limit = None
+                }
+                label { class: "flex flex-row gap-2 items-center",
+                    "Idle seeding"
+                    select { oninput: move |ev| request.make_mut().seed_idle_mode = parse_mode(&ev.value),
+                        option { value: "", "Unchanged" }
+                        option { value: "global", "Use global setting" }
+                        option { value: "single", "Stop when idle" }
+                        option { value: "unlimited", "Seed regardless of activity" }
+                    }
+                }
+                NumberField {
+                    label: "Stop when idle for (minutes)",
+                    field: "seedIdleLimit",
+                    min: 1.0,
+                    max: 1e6,
+                    integer: true,
+                    placeholder: "Unchanged",
+                    invalid: invalid,
+                    onchange: move |v| request.make_mut().seed_idle_limit = Some(v as i64),
+                    onclear: move |_| request.make_mut().seed_idle_limit = None
+                }
+                NumberField {
+                    label: "Maximum peers",
+                    field: "peer-limit",
+                    min: 1.0,
+                    max: 65535.0,
+                    integer: true,
+                    placeholder: "Unchanged",
+                    invalid: invalid,
+                    onchange: move |v| request.make_mut().peer_limit = Some(v as i64),
+                    onclear: move |_| request.make_mut().peer_limit = None
+                }
+                GroupPicker {
+                    value: None,
+                    onchange: move |group| request.make_mut().group = group
+                }
+                if let Some(message) = message.get() {
+                    rsx! { div { "{message}" } }
+                }
+                div { class: "flex flex-row justify-end gap-2",
+                    button { class: "p-1", onclick: move |_| onclose.call(()), "Close" }
+                    button {
+                        class: "p-1 bg-beige-800 dark:bg-grey-200 disabled:opacity-50",
+                        disabled: !can_save,
+                        onclick: save,
+                        if *saving.get() { "Saving..." } else { "Apply" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[inline_props]
+fn Checkbox<'a>(
+    cx: Scope,
+    label: &'a str,
+    checked: bool,
+    onchange: EventHandler<'a, FormEvent>,
+) -> Element<'a> {
+    render! {
+        label { class: "flex flex-row gap-2 items-center",
+            input { r#type: "checkbox", checked: "{checked}", oninput: move |ev| onchange.call(ev) }
+            "{label}"
+        }
+    }
+}
+
+#[inline_props]
+fn ModePicker<'a>(
+    cx: Scope,
+    label: &'a str,
+    value: SeedLimitMode,
+    onchange: EventHandler<'a, SeedLimitMode>,
+) -> Element<'a> {
+    render! {
+        label { class: "flex flex-row gap-2 items-center",
+            "{label}"
+            select {
+                oninput: move |ev| {
+                    if let Some(mode) = parse_mode(&ev.value) {
+                        onchange.call(mode);
+                    }
+                },
+                option { value: "global", selected: *value == SeedLimitMode::Global, "Use global setting" }
+                option { value: "single", selected: *value == SeedLimitMode::Single, "Use the limit below" }
+                option { value: "unlimited", selected: *value == SeedLimitMode::Unlimited, "Unlimited" }
+            }
+        }
+    }
+}
+
+/// Picks one of the daemon's bandwidth groups. A `value` of `None` means "unchanged", for bulk
+/// edits, and an empty name means no group. Hidden on daemons without bandwidth groups.
+#[inline_props]
+fn GroupPicker<'a>(
+    cx: Scope,
+    #[props(!optional)]
+    value: Option<String>,
+    onchange: EventHandler<'a, Option<String>>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    let groups = use_future(cx, (), |_| {
+        let store = store.clone();
+        async move { store.client().group_get(Vec::new()).await }
+    });
+    let Some(Ok(groups)) = groups.value() else {
+        return None;
+    };
+    let mut names: Vec<String> = groups.iter().map(|group| group.name.clone()).collect();
+    // Keep the current group visible even if it has no settings of its own.
+    if let Some(name) = value.as_ref().filter(|name| !name.is_empty()) {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    let bulk = value.is_none();
+    let current = value.as_deref().unwrap_or_default();
+
+    render! {
+        label { class: "flex flex-row gap-2 items-center",
+            "Bandwidth group"
+            select { oninput: move |ev| onchange.call(parse_group(&ev.value)),
+                if bulk {
+                    rsx! { option { value: "", "Unchanged" } }
+                }
+                option { value: "none", selected: !bulk && current.is_empty(), "None" }
+                names.iter().map(|name| rsx! {
+                    option { key: "{name}", value: "group:{name}", selected: !bulk && *name == current, "{name}" }
+                })
+            }
+        }
+    }
+}
+
+/// A yes/no setting that can also be left unchanged.
+#[inline_props]
+fn BoolPicker<'a>(cx: Scope, label: &'a str, onchange: EventHandler<'a, FormEvent>) -> Element<'a> {
+    render! {
+        label { class: "flex flex-row gap-2 items-center",
+            "{label}"
+            select { oninput: move |ev| onchange.call(ev),
+                option { value: "", "Unchanged" }
+                option { value: "on", "On" }
+                option { value: "off", "Off" }
+            }
+        }
+    }
+}
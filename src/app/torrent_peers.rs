@@ -0,0 +1,94 @@
+#![allow(non_snake_case)]
+
+use std::net::IpAddr;
+
+use dioxus::prelude::*;
+use human_bytes::human_bytes;
+
+use crate::app::use_geoip;
+use crate::transmission::client::{Peer, PeerFlag, PeersFrom};
+
+#[inline_props]
+pub fn TorrentPeers<'a>(
+    cx: Scope,
+    peers: &'a Vec<Peer>,
+    peers_from: &'a PeersFrom,
+    peers_connected: i64,
+    peers_sending_to_us: i64,
+    peers_getting_from_us: i64,
+) -> Element<'a> {
+    let geoip = use_geoip(cx);
+
+    let sources = [
+        ("Tracker", peers_from.from_tracker),
+        ("DHT", peers_from.from_dht),
+        ("PEX", peers_from.from_pex),
+        ("LPD", peers_from.from_lpd),
+        ("LTEP", peers_from.from_ltep),
+        ("Incoming", peers_from.from_incoming),
+        ("Cache", peers_from.from_cache),
+    ];
+
+    render! {
+        div { class: "flex flex-col gap-2 text-sm",
+            div { "Connected to {peers_connected} peers, downloading from {peers_sending_to_us}, uploading to {peers_getting_from_us}" }
+            div { class: "flex flex-row flex-wrap gap-x-4",
+                sources.iter().filter(|(_, count)| *count > 0).map(|(source, count)| rsx! {
+                    span { key: "{source}", "{source}: {count}" }
+                })
+            }
+            table { class: "w-full text-left",
+                thead {
+                    tr {
+                        if geoip.is_some() {
+                            rsx! { th { "" } }
+                        }
+                        th { "Address" }
+                        th { "Client" }
+                        th { class: "text-right", "Progress" }
+                        th { class: "text-right", "Down" }
+                        th { class: "text-right", "Up" }
+                        th { "Flags" }
+                    }
+                }
+                tbody {
+                    peers.iter().map(|peer| {
+                        let country = geoip
+                            .and_then(|geoip| geoip.country(peer.address.parse::<IpAddr>().ok()?))
+                            .unwrap_or_default();
+                        let progress = format!("{:.1}%", 100.0 * peer.progress);
+                        let rate_down = human_bytes(peer.rate_to_client as f64);
+                        let rate_up = human_bytes(peer.rate_to_peer as f64);
+                        let flags = peer
+                            .flags()
+                            .iter()
+                            .map(PeerFlag::description)
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        let mut connection = Vec::new();
+                        if peer.is_encrypted {
+                            connection.push("🔒");
+                        }
+                        if peer.is_utp {
+                            connection.push("µTP");
+                        }
+                        let connection = connection.join(" ");
+                        rsx! {
+                            tr { key: "{peer.address}:{peer.port}",
+                                if geoip.is_some() {
+                                    rsx! { td { "{country}" } }
+                                }
+                                td { class: "break-all", "{peer.address}" }
+                                td { "{peer.client_name}" }
+                                td { class: "text-right", "{progress}" }
+                                td { class: "text-right whitespace-nowrap", "{rate_down}/s" }
+                                td { class: "text-right whitespace-nowrap", "{rate_up}/s" }
+                                td { class: "font-mono whitespace-nowrap", title: "{flags}", "{peer.flag_str} {connection}" }
+                            }
+                        }
+                    })
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+
+use crate::app::format::format_date;
+use crate::app::use_store;
+use crate::transmission::client::{tracker_list, Tracker, TrackerState, TrackerStat};
+
+/// Parses a tracker list as typed by the user: one announce URL per line, tiers separated by blank
+/// lines.
+fn parse_tiers(text: &str) -> Vec<Vec<String>> {
+    let mut tiers = vec![Vec::new()];
+    for line in text.lines().map(str::trim) {
+        match tiers.last_mut() {
+            Some(tier) if line.is_empty() && !tier.is_empty() => tiers.push(Vec::new()),
+            Some(tier) if !line.is_empty() => tier.push(line.to_string()),
+            _ => {}
+        }
+    }
+    tiers.retain(|tier| !tier.is_empty());
+    tiers
+}
+
+fn state_label(stat: &TrackerStat) -> &'static str {
+    match stat.announce_state {
+        TrackerState::Inactive => "Inactive",
+        TrackerState::Waiting => "Waiting",
+        TrackerState::Queued => "Queued",
+        TrackerState::Active => "Announcing",
+    }
+}
+
+fn last_announce(stat: &TrackerStat) -> String {
+    if !stat.has_announced {
+        "Never".to_string()
+    } else if stat.last_announce_timed_out {
+        "Timed out".to_string()
+    } else if stat.last_announce_succeeded {
+        format!("{} peers", stat.last_announce_peer_count)
+    } else {
+        stat.last_announce_result.clone()
+    }
+}
+
+/// Trackers report -1 when they do not share a count.
+fn count(value: i64) -> String {
+    if value < 0 {
+        "-".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[inline_props]
+pub fn TorrentTrackers<'a>(
+    cx: Scope,
+    torrent_id: i64,
+    trackers: &'a Vec<Tracker>,
+    tracker_stats: &'a Vec<TrackerStat>,
+) -> Element<'a> {
+    let store = use_store(cx);
+    let editing = use_state(cx, || false);
+    let text = use_state(cx, || "".to_string());
+    let replacing = use_state::<Option<String>>(cx, || None);
+    let replacement = use_state(cx, || "".to_string());
+    let message = use_state::<Option<String>>(cx, || None);
+
+    let start_editing = move |_| {
+        text.set(tracker_list(trackers));
+        editing.set(true);
+    };
+    let save = move |_| {
+        let store = store.clone();
+        let editing = editing.to_owned();
+        let message = message.to_owned();
+        let id = *torrent_id;
+        let current = trackers.to_vec();
+        let tiers = parse_tiers(text.get());
+        cx.spawn(async move {
+            match store
+                .client()
+                .torrent_set_trackers(id, &current, tiers)
+                .await
+            {
+                Ok(_) => {
+                    message.set(None);
+                    editing.set(false);
+                    store.refresh();
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+        });
+    };
+    let replace = move |_| {
+        let Some(old) = replacing.get().clone() else {
+            return;
+        };
+        let new = replacement.trim().to_string();
+        if new.is_empty() {
+            return;
+        }
+        let store = store.clone();
+        let replacing = replacing.to_owned();
+        let message = message.to_owned();
+        cx.spawn(async move {
+            match store.client().replace_tracker_everywhere(&old, &new).await {
+                Ok(replacement) => {
+                    let changed = replacement.changed;
+                    let mut result = format!("Replaced the tracker in {changed} torrents");
+                    if let Some((_, e)) = replacement.failed.first() {
+                        let failed = replacement.failed.len();
+                        result.push_str(&format!(", {failed} failed: {e}"));
+                    } else {
+                        replacing.set(None);
+                    }
+                    message.set(Some(result));
+                    store.refresh();
+                }
+                Err(e) => message.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    render! {
+        div { class: "flex flex-col gap-2 text-sm",
+            if let Some(message) = message.get() {
+                rsx! { div { "{message}" } }
+            }
+            table { class: "w-full text-left",
+                thead {
+                    tr {
+                        th { "Tier" }
+                        th { "Tracker" }
+                        th { "State" }
+                        th { "Last announce" }
+                        th { class: "text-right", "Seeders" }
+                        th { class: "text-right", "Leechers" }
+                        th { "Next announce" }
+                        th { "" }
+                    }
+                }
+                tbody {
+                    tracker_stats.iter().map(|stat| {
+                        let backup = if stat.is_backup { " (backup)" } else { "" };
+                        let last_time = format_date(stat.last_announce_time);
+                        let next_time = format_date(stat.next_announce_time);
+                        let state = state_label(stat);
+                        let last_result = last_announce(stat);
+                        let seeders = count(stat.seeder_count);
+                        let leechers = count(stat.leecher_count);
+                        let announce = stat.announce.clone();
+                        rsx! {
+                            tr { key: "{stat.id}",
+                                td { "{stat.tier}" }
+                                td { class: "break-all", title: "{stat.announce}", "{stat.host}{backup}" }
+                                td { "{state}" }
+                                td { title: "{last_time}", "{last_result}" }
+                                td { class: "text-right", "{seeders}" }
+                                td { class: "text-right", "{leechers}" }
+                                td { class: "whitespace-nowrap", "{next_time}" }
+                                td {
+                                    button {
+                                        class: "p-1 whitespace-nowrap",
+                                        onclick: move |_| {
+                                            replacement.set(announce.clone());
+                                            replacing.set(Some(announce.clone()));
+                                        },
+                                        "Replace everywhere"
+                                    }
+                                }
+                            }
+                        }
+                    })
+                }
+            }
+            if let Some(old) = replacing.get() {
+                rsx! {
+                    div { class: "flex flex-col gap-1",
+                        span { class: "break-all", "Replace {old} in every torrent with:" }
+                        input {
+                            value: "{replacement}",
+                            oninput: move |ev| replacement.set(ev.value.clone())
+                        }
+                        div { class: "flex flex-row justify-end gap-2",
+                            button { class: "p-1", onclick: move |_| replacing.set(None), "Cancel" }
+                            button { class: "p-1 bg-beige-800 dark:bg-grey-200", onclick: replace, "Replace" }
+                        }
+                    }
+                }
+            }
+            if *editing.get() {
+                rsx! {
+                    div { class: "flex flex-col gap-1",
+                        span { "One announce URL per line, with a blank line between tiers." }
+                        textarea {
+                            class: "font-mono",
+                            rows: "8",
+                            value: "{text}",
+                            oninput: move |ev| text.set(ev.value.clone())
+                        }
+                        div { class: "flex flex-row justify-end gap-2",
+                            button { class: "p-1", onclick: move |_| editing.set(false), "Cancel" }
+                            button { class: "p-1 bg-beige-800 dark:bg-grey-200", onclick: save, "Save" }
+                        }
+                    }
+                }
+            } else {
+                rsx! {
+                    div { class: "flex flex-row justify-end",
+                        button { class: "p-1", onclick: start_editing, "Edit trackers" }
+                    }
+                }
+            }
+        }
+    }
+}
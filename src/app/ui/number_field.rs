@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use dioxus::prelude::*;
+
+/// Parses a number typed into a form, returning the message to show if it is not valid.
+fn parse_number(value: &str, min: f64, max: f64, integer: bool) -> Result<f64, String> {
+    let number: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| "Must be a number".to_string())?;
+    if integer && number.fract() != 0.0 {
+        return Err("Must be a whole number".to_string());
+    }
+    if number < min || number > max {
+        return Err(format!("Must be between {min} and {max}"));
+    }
+    Ok(number)
+}
+
+/// Adds `field` to a form's invalid fields, or removes it once its input is valid again.
+pub(crate) fn track_invalid(
+    invalid: &UseState<BTreeSet<&'static str>>,
+    field: &'static str,
+    valid: bool,
+) {
+    invalid.with_mut(|fields| {
+        if valid {
+            fields.remove(field);
+        } else {
+            fields.insert(field);
+        }
+    });
+}
+
+/// A number input that keeps what the user typed. Valid numbers go to `onchange`, and while the
+/// text is not valid `field` is kept in `invalid` so the form can refuse to save.
+///
+/// With an `onclear` handler the input may also be left empty, e.g. for "unchanged" in bulk edits.
+#[inline_props]
+pub(crate) fn NumberField<'a>(
+    cx: Scope,
+    label: &'a str,
+    field: &'static str,
+    value: Option<f64>,
+    min: f64,
+    max: f64,
+    integer: bool,
+    placeholder: Option<&'a str>,
+    invalid: &'a UseState<BTreeSet<&'static str>>,
+    onchange: EventHandler<'a, f64>,
+    onclear: Option<EventHandler<'a, ()>>,
+) -> Element<'a> {
+    let text = use_state(cx, || {
+        value.map(|value| value.to_string()).unwrap_or_default()
+    });
+    let parse = move |text: &str| -> Result<Option<f64>, String> {
+        if onclear.is_some() && text.trim().is_empty() {
+            return Ok(None);
+        }
+        parse_number(text, *min, *max, *integer).map(Some)
+    };
+    let error = parse(text.get()).err();
+    let placeholder = placeholder.unwrap_or_default();
+
+    render! {
+        label { class: "flex flex-col",
+            "{label}"
+            input {
+                r#type: "number",
+                value: "{text}",
+                placeholder: "{placeholder}",
+                oninput: move |ev| {
+                    text.set(ev.value.clone());
+                    let parsed = parse(&ev.value);
+                    track_invalid(invalid, field, parsed.is_ok());
+                    match parsed {
+                        Ok(Some(number)) => onchange.call(number),
+                        Ok(None) => {
+                            if let Some(onclear) = onclear {
+                                onclear.call(());
+                            }
+                        }
+                        Err(_) => {}
+                    }
+                }
+            }
+            if let Some(error) = error {
+                rsx! { span { class: "text-red-100 dark:text-red-300", "{error}" } }
+            }
+        }
+    }
+}
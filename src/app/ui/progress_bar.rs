@@ -124,7 +124,7 @@ pub(crate) fn ProgressBar<'a>(
     status: &'a TorrentStatus,
     piece_count: i64,
     pieces: &'a str,
-) -> Element {
+) -> Element<'a> {
     let color_piece_complete = match status {
         TorrentStatus::Seeding => "bg-green-200 dark:bg-green-300",
         TorrentStatus::Downloading => "bg-blue-100 dark:bg-blue-300",
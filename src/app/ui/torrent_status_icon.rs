@@ -8,7 +8,7 @@ use dioxus_free_icons::Icon;
 use crate::transmission::client::TorrentStatus;
 
 #[inline_props]
-pub(crate) fn TorrentStatusIcon<'a>(cx: Scope, status: &'a TorrentStatus) -> Element {
+pub(crate) fn TorrentStatusIcon<'a>(cx: Scope, status: &'a TorrentStatus) -> Element<'a> {
     match status {
         TorrentStatus::Stopped => render!( Icon { height: 16, width: 16, icon: FiOctagonFill } ),
         TorrentStatus::QueuedVerify => render!( Icon { height: 16, width: 16, icon: FiList } ),
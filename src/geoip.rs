@@ -0,0 +1,42 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+
+/// Offline country lookups from a MaxMind-format (MMDB) database, e.g. GeoLite2-Country.
+pub struct GeoIp {
+    reader: Reader<Vec<u8>>,
+}
+
+impl fmt::Debug for GeoIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeoIp")
+            .field("database_type", &self.reader.metadata.database_type)
+            .finish()
+    }
+}
+
+/// There is only ever one database per server, so compare by identity.
+impl PartialEq for GeoIp {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl GeoIp {
+    pub fn open(path: &Path) -> Result<Self, MaxMindDBError> {
+        Ok(GeoIp {
+            reader: Reader::open_readfile(path)?,
+        })
+    }
+
+    /// ISO 3166-1 alpha-2 code of the country `address` is in, if the database knows it.
+    pub fn country(&self, address: IpAddr) -> Option<String> {
+        let country: geoip2::Country = self.reader.lookup(address).ok()?;
+        country
+            .country
+            .and_then(|country| country.iso_code)
+            .map(|code| code.to_string())
+    }
+}
@@ -4,9 +4,50 @@ function main() {
     console.error("[Dioxus] Could not find an element with ID 'main'");
   } else {
     window.ipc = new IPC(root);
+    forwardFileUploads(root);
+    syncLocation(root);
   }
 }
 
+// The app runs on the server, so it cannot change the address bar itself. It renders the URL it
+// wants in <span id="rt-location">, which we mirror with history.replaceState.
+function syncLocation(root) {
+  const observer = new MutationObserver(() => {
+    const target = document.getElementById("rt-location");
+    if (target === null) {
+      return;
+    }
+    const location = target.textContent;
+    if (location && location !== window.location.pathname + window.location.search) {
+      window.history.replaceState(null, "", location);
+    }
+  });
+  observer.observe(root, { childList: true, subtree: true, characterData: true });
+}
+
+// LiveView only sees serialized event values, not files. When a file is picked in an
+// <input type="file" id="foo">, read it and send it as base64 through <input type="hidden" id="foo-base64">.
+function forwardFileUploads(root) {
+  root.addEventListener("change", (event) => {
+    const input = event.target;
+    if (input.type !== "file" || !input.id) {
+      return;
+    }
+    const target = document.getElementById(`${input.id}-base64`);
+    const file = input.files[0];
+    if (target === null || file === undefined) {
+      return;
+    }
+    const reader = new FileReader();
+    reader.onload = () => {
+      // Strip the "data:<mime>;base64," prefix
+      target.value = reader.result.substring(reader.result.indexOf(",") + 1);
+      target.dispatchEvent(new Event("input", { bubbles: true }));
+    };
+    reader.readAsDataURL(file);
+  });
+}
+
 function log(msg) {
   "use strict";
   console.log(`[Dioxus] ${msg}`)
@@ -24,7 +65,8 @@ class IPC {
     }
 
     const connect = () => {
-      this.ws = new WebSocket(`${WS_ADDR}?initial_route=${window.location.pathname}`);
+      const initialRoute = encodeURIComponent(window.location.pathname + window.location.search);
+      this.ws = new WebSocket(`${WS_ADDR}?initial_route=${initialRoute}`);
       this.ws.onopen = onopen;
       this.ws.onmessage = onmessage;
       this.ws.onclose = onclose;
@@ -1,4 +1,5 @@
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use axum::extract::connect_info::ConnectInfo;
 use axum::extract::Query;
@@ -7,11 +8,14 @@ use axum::{extract::WebSocketUpgrade, response::Html, Router};
 use clap::Parser;
 use color_eyre::Report;
 use dioxus_interpreter_js::INTERPRETER_JS;
+use geoip::GeoIp;
 use serde::Deserialize;
+use store::Store;
 use tracing::{debug, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
 use tracing_tree::HierarchicalLayer;
-use user_config::load_config;
+use transmission::client::{Client, ClientBuilder};
+use user_config::{load_config, UserConfig};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -22,11 +26,13 @@ struct Args {
 }
 
 pub mod app;
+pub mod geoip;
+pub mod store;
 pub mod transmission;
 pub mod user_config;
 
-static TAILWIND_CSS: &'static str = include_str!(concat!(env!("OUT_DIR"), "/tailwind.css"));
-static MAIN_JS: &'static str = include_str!("./main.js");
+static TAILWIND_CSS: &str = include_str!(concat!(env!("OUT_DIR"), "/tailwind.css"));
+static MAIN_JS: &str = include_str!("./main.js");
 
 pub fn interpreter_glue(url: &str) -> String {
     format!(
@@ -65,6 +71,23 @@ pub fn tracing_teardown() {
     opentelemetry::global::shutdown_tracer_provider();
 }
 
+fn transmission_client(config: &UserConfig) -> Result<Client, Report> {
+    let mut builder = ClientBuilder::new()
+        .transmission_url(config.transmission_url.clone())
+        .timeout(config.request_timeout())
+        .accept_invalid_certs(config.accept_invalid_certs);
+    if let Some(username) = &config.username {
+        builder = builder.username(username.clone());
+    }
+    if let Some(password) = config.resolve_password()? {
+        builder = builder.password(password);
+    }
+    for path in &config.ca_certificates {
+        builder = builder.root_certificate(&std::fs::read(path)?)?;
+    }
+    Ok(builder.build()?)
+}
+
 #[derive(Deserialize)]
 struct WebSocketQuery {
     initial_route: Option<String>,
@@ -81,7 +104,16 @@ async fn main() -> Result<(), Report> {
 
     debug!("Loaded config: {:?}", &config);
 
-    let addr: std::net::SocketAddr = ([10, 0, 0, 171], 3030).into();
+    let addr = config.listen_address;
+
+    let client = transmission_client(&config)?;
+    let store = Store::new(client);
+    store.spawn(config.poll_frequency());
+
+    let geoip = match &config.geoip_database {
+        Some(path) => Some(Arc::new(GeoIp::open(path)?)),
+        None => None,
+    };
 
     let view = dioxus_liveview::LiveViewPool::new();
     // let tailwind_css = Asset::get("tailwind.css").unwrap();
@@ -104,27 +136,39 @@ async fn main() -> Result<(), Report> {
                 </html>
                 "#,
         // Create the glue code to connect to the WebSocket on the "/ws" route
-        glue = interpreter_glue(&format!("ws://{addr}/ws")),
+        glue = interpreter_glue(&config.websocket_url()),
         style = TAILWIND_CSS
     );
     let html_root = html.clone();
     let html_path = html.clone();
+    let ws_config = config.clone();
+    let ws_store = store.clone();
+    let ws_geoip = geoip.clone();
 
-    let router = Router::new()
+    let app_router = Router::new()
         .route(
             "/ws",
             get(
                 move |ws: WebSocketUpgrade,
                       query: Query<WebSocketQuery>,
                       ConnectInfo(addr): ConnectInfo<SocketAddr>| async move {
-                    let initial_route = query.initial_route.clone().unwrap_or("/".to_string());
+                    let initial_route =
+                        ws_config.route_path(query.initial_route.as_deref().unwrap_or("/"));
+                    let config = ws_config.clone();
+                    let store = ws_store.clone();
+                    let geoip = ws_geoip.clone();
                     ws.on_upgrade(move |socket| async move {
                         info!("WebSocket [{:#?}]: Accepted connection", addr);
                         _ = view
                             .launch_with_props(
                                 dioxus_liveview::axum_socket(socket),
                                 app::root,
-                                app::rootProps { initial_route },
+                                app::rootProps {
+                                    initial_route,
+                                    config,
+                                    store,
+                                    geoip,
+                                },
                             )
                             .await;
                         info!("WebSocket [{:#?}]: Connection dropped", addr);
@@ -135,9 +179,14 @@ async fn main() -> Result<(), Report> {
         .route("/*rest", get(move || async move { Html(html_path) }))
         .route("/", get(move || async move { Html(html_root) }));
 
-    info!("Listening on http://{addr}");
+    let router = match config.base_path().as_str() {
+        "/" => app_router,
+        base_path => Router::new().nest(base_path, app_router),
+    };
+
+    info!("Listening on http://{addr}{}", config.base_path());
 
-    axum::Server::bind(&addr.to_string().parse().unwrap())
+    axum::Server::bind(&addr)
         .serve(router.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
@@ -0,0 +1,297 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tracing::warn;
+
+use crate::transmission::client::{
+    Client, SessionSettings, SessionStats, TorrentIds, TorrentIdsKeyword, TorrentSummary,
+    TorrentSummaryResponse,
+};
+use crate::transmission::Error;
+
+/// How many recently used download directories to remember.
+const MAX_RECENT_DIRS: usize = 8;
+
+/// How far back Transmission looks for `recently-active` torrents. Polling less often than this
+/// would miss changes, so then every poll gets every torrent.
+const RECENTLY_ACTIVE_WINDOW: Duration = Duration::from_secs(60);
+
+/// How often to get every torrent even while only asking for recently active ones, as a safety
+/// net for changes Transmission doesn't count as activity.
+const FULL_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// The latest state polled from Transmission.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub torrents: Vec<TorrentSummary>,
+    pub session_stats: Option<SessionStats>,
+    pub session: Option<SessionSettings>,
+    /// Why the last poll failed, if it did. The rest of the snapshot keeps the last good values.
+    pub error: Option<Error>,
+}
+
+/// Server-wide Transmission state. A single task polls the daemon and every LiveView session
+/// subscribes to the published snapshots instead of polling on its own.
+#[derive(Debug)]
+pub struct Store {
+    client: Arc<Client>,
+    snapshot: watch::Sender<Snapshot>,
+    refresh: Notify,
+    /// Directories torrents were recently moved to, most recent first.
+    recent_dirs: Mutex<Vec<String>>,
+    /// When every torrent was last fetched, or `None` if the next poll should fetch every torrent.
+    last_full_refresh: Mutex<Option<Instant>>,
+}
+
+/// Stores are shared by reference, so two handles are equal only if they point at the same store.
+impl PartialEq for Store {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Store {
+    pub fn new(client: Client) -> Arc<Self> {
+        let (snapshot, _) = watch::channel(Snapshot::default());
+        Arc::new(Store {
+            client: Arc::new(client),
+            snapshot,
+            refresh: Notify::new(),
+            recent_dirs: Mutex::new(Vec::new()),
+            last_full_refresh: Mutex::new(None),
+        })
+    }
+
+    /// Starts polling Transmission every `poll_frequency`, or sooner when `refresh` is called.
+    pub fn spawn(self: &Arc<Self>, poll_frequency: Duration) -> JoinHandle<()> {
+        let store = self.clone();
+        let use_deltas = poll_frequency < RECENTLY_ACTIVE_WINDOW;
+        tokio::spawn(async move {
+            loop {
+                store.poll(use_deltas).await;
+                // Either the timer elapsing or a refresh request starts the next poll.
+                let _ = timeout(poll_frequency, store.refresh.notified()).await;
+            }
+        })
+    }
+
+    pub fn client(&self) -> &Arc<Client> {
+        &self.client
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Snapshot> {
+        self.snapshot.subscribe()
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    /// Polls again without waiting for the rest of the interval, e.g. after running an action.
+    pub fn refresh(&self) {
+        self.refresh.notify_one();
+    }
+
+    /// Requests a refresh and waits for the next snapshot to be published.
+    pub async fn refreshed(&self) {
+        let mut updates = self.subscribe();
+        self.refresh();
+        let _ = updates.changed().await;
+    }
+
+    /// Publishes a torrent's new name straight away, without waiting for the next poll.
+    pub fn rename_torrent(&self, id: i64, name: &str) {
+        self.snapshot.send_modify(|snapshot| {
+            if let Some(torrent) = snapshot.torrents.iter_mut().find(|t| t.id as i64 == id) {
+                torrent.name = name.to_string();
+            }
+        });
+    }
+
+    /// Every label in use, sorted and without duplicates that only differ in case.
+    pub fn labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self
+            .snapshot
+            .borrow()
+            .torrents
+            .iter()
+            .flat_map(|torrent| torrent.labels.iter().cloned())
+            .collect();
+        labels.sort_by_key(|label| label.to_lowercase());
+        labels.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        labels
+    }
+
+    pub fn recent_dirs(&self) -> Vec<String> {
+        self.recent_dirs.lock().clone()
+    }
+
+    /// Moves `dir` to the front of the recent directories.
+    pub fn remember_dir(&self, dir: &str) {
+        let mut recent_dirs = self.recent_dirs.lock();
+        recent_dirs.retain(|recent| recent != dir);
+        recent_dirs.insert(0, dir.to_string());
+        recent_dirs.truncate(MAX_RECENT_DIRS);
+    }
+
+    /// Polls Transmission. With `use_deltas`, only gets recently active torrents between full
+    /// refreshes.
+    #[tracing::instrument(skip(self))]
+    async fn poll(&self, use_deltas: bool) {
+        let recent_full_refresh = matches!(
+            *self.last_full_refresh.lock(),
+            Some(last) if last.elapsed() < FULL_REFRESH_INTERVAL
+        );
+        let full = !use_deltas || !recent_full_refresh;
+        let ids = (!full).then_some(TorrentIds::Keyword(TorrentIdsKeyword::RecentlyActive));
+        // The rest of the session settings only change when someone edits them, so between full
+        // refreshes only the speed limits shown in the stats bar are fetched.
+        let full_session = full || self.snapshot.borrow().session.is_none();
+        let session = async {
+            if full_session {
+                self.client.session_settings().await
+            } else {
+                self.client.session_speed_limits().await
+            }
+        };
+        let started = Instant::now();
+        let (torrents, session_stats, session) = tokio::join!(
+            self.client.torrent_summary(ids),
+            self.client.session_stats(),
+            session
+        );
+
+        self.snapshot.send_modify(|snapshot| {
+            snapshot.error = None;
+            match torrents {
+                Ok(response) if full => {
+                    snapshot.torrents = response.arguments.torrents;
+                    *self.last_full_refresh.lock() = Some(started);
+                }
+                Ok(response) => merge_torrents(&mut snapshot.torrents, response.arguments),
+                Err(e) => {
+                    warn!("Failed to poll torrents: {e}");
+                    snapshot.error = Some(e);
+                    // Changes may have been missed, so start over with every torrent.
+                    *self.last_full_refresh.lock() = None;
+                }
+            }
+            match session_stats {
+                Ok(response) => snapshot.session_stats = Some(response.arguments),
+                Err(e) => {
+                    warn!("Failed to poll session stats: {e}");
+                    snapshot.error.get_or_insert(e);
+                }
+            }
+            match session {
+                Ok(session) if full_session => snapshot.session = Some(session),
+                Ok(limits) => {
+                    if let Some(session) = snapshot.session.as_mut() {
+                        session.copy_speed_limits(&limits);
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to poll session settings: {e}");
+                    snapshot.error.get_or_insert(e);
+                }
+            }
+        });
+    }
+}
+
+/// Applies a `recently-active` response: changed torrents replace their old versions, new ones
+/// are added and removed ones dropped.
+fn merge_torrents(torrents: &mut Vec<TorrentSummary>, changes: TorrentSummaryResponse) {
+    torrents.retain(|torrent| !changes.removed.contains(&(torrent.id as i64)));
+    for changed in changes.torrents {
+        match torrents.iter_mut().find(|torrent| torrent.id == changed.id) {
+            Some(torrent) => *torrent = changed,
+            None => torrents.push(changed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transmission::client::TorrentStatus;
+
+    fn torrent(id: i64, name: &str) -> TorrentSummary {
+        TorrentSummary {
+            id: id as f64,
+            name: name.to_string(),
+            download_dir: "/downloads".to_string(),
+            percent_complete: 0.0,
+            percent_done: 0.0,
+            status: TorrentStatus::Downloading,
+            size_when_done: 0.0,
+            piece_count: 0,
+            pieces: String::new(),
+            eta: -1.0,
+            peers_connected: 0,
+            peers_getting_from_us: 0,
+            peers_sending_to_us: 0,
+            rate_download: 0,
+            rate_upload: 0,
+            upload_ratio: 0.0,
+            queue_position: 0,
+            error: 0,
+            trackers: Vec::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    fn names(torrents: &[TorrentSummary]) -> Vec<&str> {
+        torrents
+            .iter()
+            .map(|torrent| torrent.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn changed_torrents_replace_old_ones() {
+        let mut torrents = vec![torrent(1, "Debian"), torrent(2, "Fedora")];
+        merge_torrents(
+            &mut torrents,
+            TorrentSummaryResponse {
+                torrents: vec![torrent(2, "Fedora 38")],
+                removed: Vec::new(),
+            },
+        );
+        assert_eq!(names(&torrents), vec!["Debian", "Fedora 38"]);
+    }
+
+    #[test]
+    fn new_torrents_are_added() {
+        let mut torrents = vec![torrent(1, "Debian")];
+        merge_torrents(
+            &mut torrents,
+            TorrentSummaryResponse {
+                torrents: vec![torrent(1, "Debian 12"), torrent(3, "Arch")],
+                removed: Vec::new(),
+            },
+        );
+        assert_eq!(names(&torrents), vec!["Debian 12", "Arch"]);
+    }
+
+    #[test]
+    fn removed_torrents_are_dropped() {
+        let mut torrents = vec![
+            torrent(1, "Debian"),
+            torrent(2, "Fedora"),
+            torrent(3, "Arch"),
+        ];
+        merge_torrents(
+            &mut torrents,
+            TorrentSummaryResponse {
+                torrents: Vec::new(),
+                removed: vec![1, 3, 4],
+            },
+        );
+        assert_eq!(names(&torrents), vec!["Fedora"]);
+    }
+}
@@ -1,7 +1,5 @@
 #![allow(non_snake_case)]
 
-use color_eyre::Result;
-use http::header::HeaderMap;
 use http::StatusCode;
 use parking_lot::Mutex;
 use reqwest::redirect::Policy;
@@ -9,49 +7,407 @@ use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_repr::*;
-use std::error::Error;
-use std::sync::Arc;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
+use crate::transmission::{json_rpc, Error, Result};
+
+const SESSION_ID_HEADER: &str = "X-Transmission-Session-Id";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetSessionRequest {
+    /// Empty to get every field.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub fields: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetSessionResponse {
     pub version: Option<String>,
+    #[serde(rename = "rpc-version", alias = "rpc_version")]
+    pub rpc_version: Option<i64>,
+    /// Only reported by Transmission 4.0 and later.
+    #[serde(rename = "rpc-version-semver", alias = "rpc_version_semver")]
+    pub rpc_version_semver: Option<String>,
+}
+
+/// The wire format `Client` uses to talk to the daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// `method`, `arguments`, `result` and `tag`, with a mix of camelCase and kebab-case names.
+    Legacy,
+    /// JSON-RPC 2.0 with snake_case names, from Transmission 4.1.
+    JsonRpc,
+}
+
+impl Protocol {
+    /// JSON-RPC 2.0 arrived in RPC version 6.0.0 (Transmission 4.1). Older daemons don't report
+    /// `rpc-version-semver` at all.
+    fn detect(rpc_version_semver: Option<&str>) -> Protocol {
+        let major = rpc_version_semver
+            .and_then(|semver| semver.split('.').next())
+            .and_then(|major| major.parse::<u64>().ok());
+        match major {
+            Some(major) if major >= 6 => Protocol::JsonRpc,
+            _ => Protocol::Legacy,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Stats {
+    #[serde(alias = "uploaded_bytes")]
     pub uploaded_bytes: f64,
+    #[serde(alias = "downloaded_bytes")]
     pub downloaded_bytes: f64,
+    #[serde(alias = "files_added")]
     pub files_added: f64,
+    #[serde(alias = "session_count")]
     pub session_count: f64,
+    #[serde(alias = "seconds_active")]
     pub seconds_active: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionStats {
+    #[serde(alias = "active_torrent_count")]
     pub active_torrent_count: f64,
+    #[serde(alias = "download_speed")]
     pub download_speed: f64,
+    #[serde(alias = "paused_torrent_count")]
     pub paused_torrent_count: f64,
+    #[serde(alias = "torrent_count")]
     pub torrent_count: f64,
+    #[serde(alias = "upload_speed")]
     pub upload_speed: f64,
-    #[serde(rename = "cumulative-stats")]
+    #[serde(rename = "cumulative-stats", alias = "cumulative_stats")]
     pub cumulative_stats: Stats,
-    #[serde(rename = "current-stats")]
+    #[serde(rename = "current-stats", alias = "current_stats")]
     pub current_stats: Stats,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Encryption {
+    Required,
+    #[default]
+    Preferred,
+    Tolerated,
+}
+
+/// The daemon's settings as returned by `session-get`. Speeds are in kB/s and times of day in
+/// minutes after midnight. Fields a daemon does not know about are left at their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct SessionSettings {
+    pub version: String,
+    #[serde(alias = "rpc_version")]
+    pub rpc_version: i64,
+    #[serde(alias = "config_dir")]
+    pub config_dir: String,
+
+    #[serde(alias = "speed_limit_down")]
+    pub speed_limit_down: i64,
+    #[serde(alias = "speed_limit_down_enabled")]
+    pub speed_limit_down_enabled: bool,
+    #[serde(alias = "speed_limit_up")]
+    pub speed_limit_up: i64,
+    #[serde(alias = "speed_limit_up_enabled")]
+    pub speed_limit_up_enabled: bool,
+
+    #[serde(alias = "alt_speed_down")]
+    pub alt_speed_down: i64,
+    #[serde(alias = "alt_speed_up")]
+    pub alt_speed_up: i64,
+    #[serde(alias = "alt_speed_enabled")]
+    pub alt_speed_enabled: bool,
+    #[serde(alias = "alt_speed_time_enabled")]
+    pub alt_speed_time_enabled: bool,
+    #[serde(alias = "alt_speed_time_begin")]
+    pub alt_speed_time_begin: i64,
+    #[serde(alias = "alt_speed_time_end")]
+    pub alt_speed_time_end: i64,
+    /// Bitmask of days, starting with Sunday = 1.
+    #[serde(alias = "alt_speed_time_day")]
+    pub alt_speed_time_day: i64,
+
+    #[serde(alias = "peer_limit_global")]
+    pub peer_limit_global: i64,
+    #[serde(alias = "peer_limit_per_torrent")]
+    pub peer_limit_per_torrent: i64,
+
+    #[serde(alias = "download_dir")]
+    pub download_dir: String,
+    #[serde(alias = "incomplete_dir")]
+    pub incomplete_dir: String,
+    #[serde(alias = "incomplete_dir_enabled")]
+    pub incomplete_dir_enabled: bool,
+    #[serde(alias = "rename_partial_files")]
+    pub rename_partial_files: bool,
+    #[serde(alias = "start_added_torrents")]
+    pub start_added_torrents: bool,
+    #[serde(alias = "trash_original_torrent_files")]
+    pub trash_original_torrent_files: bool,
+    #[serde(alias = "cache_size_mb")]
+    pub cache_size_mb: i64,
+
+    #[serde(alias = "download_queue_enabled")]
+    pub download_queue_enabled: bool,
+    #[serde(alias = "download_queue_size")]
+    pub download_queue_size: i64,
+    #[serde(alias = "seed_queue_enabled")]
+    pub seed_queue_enabled: bool,
+    #[serde(alias = "seed_queue_size")]
+    pub seed_queue_size: i64,
+    #[serde(alias = "queue_stalled_enabled")]
+    pub queue_stalled_enabled: bool,
+    #[serde(alias = "queue_stalled_minutes")]
+    pub queue_stalled_minutes: i64,
+
+    #[serde(rename = "seedRatioLimit", alias = "seed_ratio_limit")]
+    pub seed_ratio_limit: f64,
+    #[serde(rename = "seedRatioLimited", alias = "seed_ratio_limited")]
+    pub seed_ratio_limited: bool,
+    #[serde(alias = "idle_seeding_limit")]
+    pub idle_seeding_limit: i64,
+    #[serde(alias = "idle_seeding_limit_enabled")]
+    pub idle_seeding_limit_enabled: bool,
+
+    pub encryption: Encryption,
+    #[serde(alias = "dht_enabled")]
+    pub dht_enabled: bool,
+    #[serde(alias = "pex_enabled")]
+    pub pex_enabled: bool,
+    #[serde(alias = "lpd_enabled")]
+    pub lpd_enabled: bool,
+    #[serde(alias = "utp_enabled")]
+    pub utp_enabled: bool,
+
+    #[serde(alias = "peer_port")]
+    pub peer_port: i64,
+    #[serde(alias = "peer_port_random_on_start")]
+    pub peer_port_random_on_start: bool,
+    #[serde(alias = "port_forwarding_enabled")]
+    pub port_forwarding_enabled: bool,
+
+    #[serde(alias = "blocklist_enabled")]
+    pub blocklist_enabled: bool,
+    #[serde(alias = "blocklist_url")]
+    pub blocklist_url: String,
+    #[serde(alias = "blocklist_size")]
+    pub blocklist_size: i64,
+}
+
+impl SessionSettings {
+    /// The `session-get` fields `copy_speed_limits` reads.
+    const SPEED_LIMIT_FIELDS: [&'static str; 7] = [
+        "speed-limit-down",
+        "speed-limit-down-enabled",
+        "speed-limit-up",
+        "speed-limit-up-enabled",
+        "alt-speed-down",
+        "alt-speed-up",
+        "alt-speed-enabled",
+    ];
+
+    /// Takes the speed limits and turtle mode from `limits`, keeping every other setting.
+    pub fn copy_speed_limits(&mut self, limits: &SessionSettings) {
+        self.speed_limit_down = limits.speed_limit_down;
+        self.speed_limit_down_enabled = limits.speed_limit_down_enabled;
+        self.speed_limit_up = limits.speed_limit_up;
+        self.speed_limit_up_enabled = limits.speed_limit_up_enabled;
+        self.alt_speed_down = limits.alt_speed_down;
+        self.alt_speed_up = limits.alt_speed_up;
+        self.alt_speed_enabled = limits.alt_speed_enabled;
+    }
+}
+
+/// Arguments for `session-set`. Only fields that are set are sent, so build one with `changes`
+/// rather than sending every setting back.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SessionSetRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_limit_down: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_limit_down_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_limit_up: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_limit_up_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_speed_down: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_speed_up: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_speed_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_speed_time_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_speed_time_begin: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_speed_time_end: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_speed_time_day: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_limit_global: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_limit_per_torrent: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incomplete_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incomplete_dir_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rename_partial_files: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_added_torrents: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_original_torrent_files: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_size_mb: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_queue_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_queue_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_queue_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_queue_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_stalled_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_stalled_minutes: Option<i64>,
+    #[serde(rename = "seedRatioLimit", skip_serializing_if = "Option::is_none")]
+    pub seed_ratio_limit: Option<f64>,
+    #[serde(rename = "seedRatioLimited", skip_serializing_if = "Option::is_none")]
+    pub seed_ratio_limited: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_seeding_limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_seeding_limit_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<Encryption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dht_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pex_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lpd_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utp_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_port: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_port_random_on_start: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_forwarding_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocklist_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocklist_url: Option<String>,
+}
+
+impl SessionSetRequest {
+    /// The settings that differ between `old` and `new`. Read-only fields are ignored.
+    pub fn changes(old: &SessionSettings, new: &SessionSettings) -> Self {
+        let mut request = Self::default();
+        macro_rules! diff {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if old.$field != new.$field {
+                        request.$field = Some(new.$field.clone());
+                    }
+                )*
+            };
+        }
+        diff!(
+            speed_limit_down,
+            speed_limit_down_enabled,
+            speed_limit_up,
+            speed_limit_up_enabled,
+            alt_speed_down,
+            alt_speed_up,
+            alt_speed_enabled,
+            alt_speed_time_enabled,
+            alt_speed_time_begin,
+            alt_speed_time_end,
+            alt_speed_time_day,
+            peer_limit_global,
+            peer_limit_per_torrent,
+            download_dir,
+            incomplete_dir,
+            incomplete_dir_enabled,
+            rename_partial_files,
+            start_added_torrents,
+            trash_original_torrent_files,
+            cache_size_mb,
+            download_queue_enabled,
+            download_queue_size,
+            seed_queue_enabled,
+            seed_queue_size,
+            queue_stalled_enabled,
+            queue_stalled_minutes,
+            seed_ratio_limit,
+            seed_ratio_limited,
+            idle_seeding_limit,
+            idle_seeding_limit_enabled,
+            encryption,
+            dht_enabled,
+            pex_enabled,
+            lpd_enabled,
+            utp_enabled,
+            peer_port,
+            peer_port_random_on_start,
+            port_forwarding_enabled,
+            blocklist_enabled,
+            blocklist_url,
+        );
+        request
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Which torrents `torrent-get` returns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TorrentIds {
+    List(Vec<i64>),
+    Keyword(TorrentIdsKeyword),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TorrentIdsKeyword {
+    /// Torrents that changed in the last minute. The response also lists recently removed ids.
+    RecentlyActive,
+}
+
+/// How `torrent-get` lays out its torrents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TorrentFormat {
+    /// One object per torrent.
+    Objects,
+    /// A row of field names followed by one row of values per torrent, which is much smaller for
+    /// many torrents. Needs RPC version 16 (Transmission 3.0).
+    Table,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetTorrentRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ids: Option<Vec<String>>,
+    pub ids: Option<TorrentIds>,
     pub fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<TorrentFormat>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,17 +416,12 @@ pub struct TorrentActionRequest {
     pub ids: Option<Vec<i64>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TorrentStartRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ids: Option<Vec<f64>>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Torrent {
     pub id: Option<f64>,
     pub name: Option<String>,
+    #[serde(alias = "percent_complete")]
     pub percent_complete: Option<f64>,
 }
 
@@ -79,7 +430,7 @@ pub struct GetTorrentResponse {
     pub torrents: Vec<Torrent>,
 }
 
-#[derive(Debug, Clone, Serialize_repr, Deserialize_repr, PartialOrd, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize_repr, Deserialize_repr, PartialOrd, PartialEq)]
 #[repr(u8)]
 pub enum TorrentStatus {
     Stopped = 0,
@@ -96,23 +447,812 @@ pub enum TorrentStatus {
 pub struct TorrentSummary {
     pub id: f64,
     pub name: String,
+    #[serde(alias = "download_dir")]
+    pub download_dir: String,
+    #[serde(alias = "percent_complete")]
     pub percent_complete: f64,
+    #[serde(alias = "percent_done")]
     pub percent_done: f64,
     pub status: TorrentStatus,
+    #[serde(alias = "size_when_done")]
     pub size_when_done: f64,
+    #[serde(alias = "piece_count")]
     pub piece_count: i64,
     pub pieces: String,
     pub eta: f64,
+    #[serde(alias = "peers_connected")]
     pub peers_connected: i64,
+    #[serde(alias = "peers_getting_from_us")]
     pub peers_getting_from_us: i64,
+    #[serde(alias = "peers_sending_to_us")]
     pub peers_sending_to_us: i64,
+    #[serde(alias = "rate_download")]
     pub rate_download: i64,
+    #[serde(alias = "rate_upload")]
     pub rate_upload: i64,
+    /// -1 if not available, -2 if infinite.
+    #[serde(alias = "upload_ratio")]
+    pub upload_ratio: f64,
+    /// Position in the download or seed queue, starting at 0.
+    #[serde(alias = "queue_position")]
+    pub queue_position: i64,
+    /// 0 if there is no error.
+    pub error: i64,
+    pub trackers: Vec<Tracker>,
+    /// Only reported by Transmission 3.0 and later.
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentSetLocationRequest {
+    pub ids: Vec<i64>,
+    pub location: String,
+    /// Move the downloaded data to `location`, rather than looking for it there.
+    #[serde(rename = "move")]
+    pub move_data: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentRenamePathRequest {
+    pub ids: Vec<i64>,
+    pub path: String,
+    pub name: String,
+}
+
+/// What `torrent-rename-path` renamed: the old `path` within the torrent now ends in `name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenamedPath {
+    pub id: i64,
+    pub path: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreeSpaceRequest {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreeSpace {
+    pub path: String,
+    #[serde(rename = "size-bytes", alias = "size_bytes")]
+    pub size_bytes: f64,
+    /// Only reported by Transmission 4.0 and later.
+    #[serde(rename = "total_size")]
+    pub total_size: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentRemoveRequest {
+    pub ids: Vec<i64>,
+    #[serde(rename = "delete-local-data")]
+    pub delete_local_data: bool,
+}
+
+/// Arguments for `torrent-set`. Only fields that are set are sent, so unset fields keep their
+/// current value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TorrentSetRequest {
+    pub ids: Vec<i64>,
+    #[serde(rename = "files-wanted", skip_serializing_if = "Option::is_none")]
+    pub files_wanted: Option<Vec<usize>>,
+    #[serde(rename = "files-unwanted", skip_serializing_if = "Option::is_none")]
+    pub files_unwanted: Option<Vec<usize>>,
+    #[serde(rename = "priority-high", skip_serializing_if = "Option::is_none")]
+    pub priority_high: Option<Vec<usize>>,
+    #[serde(rename = "priority-normal", skip_serializing_if = "Option::is_none")]
+    pub priority_normal: Option<Vec<usize>>,
+    #[serde(rename = "priority-low", skip_serializing_if = "Option::is_none")]
+    pub priority_low: Option<Vec<usize>>,
+    /// Replaces all trackers, see `tracker_list`. Needs RPC version 17 (Transmission 4.0).
+    #[serde(rename = "trackerList", skip_serializing_if = "Option::is_none")]
+    pub tracker_list: Option<String>,
+    /// Announce URLs to add. Superseded by `tracker_list`.
+    #[serde(rename = "trackerAdd", skip_serializing_if = "Option::is_none")]
+    pub tracker_add: Option<Vec<String>>,
+    /// Ids of trackers to remove. Superseded by `tracker_list`.
+    #[serde(rename = "trackerRemove", skip_serializing_if = "Option::is_none")]
+    pub tracker_remove: Option<Vec<i64>>,
+    /// Pairs of tracker id and new announce URL. Superseded by `tracker_list`.
+    #[serde(
+        rename = "trackerReplace",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_tracker_replace",
+        skip_deserializing
+    )]
+    pub tracker_replace: Option<Vec<(i64, String)>>,
+    #[serde(rename = "downloadLimit", skip_serializing_if = "Option::is_none")]
+    pub download_limit: Option<i64>,
+    #[serde(rename = "downloadLimited", skip_serializing_if = "Option::is_none")]
+    pub download_limited: Option<bool>,
+    #[serde(rename = "uploadLimit", skip_serializing_if = "Option::is_none")]
+    pub upload_limit: Option<i64>,
+    #[serde(rename = "uploadLimited", skip_serializing_if = "Option::is_none")]
+    pub upload_limited: Option<bool>,
+    #[serde(
+        rename = "honorsSessionLimits",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub honors_session_limits: Option<bool>,
+    #[serde(rename = "seedRatioLimit", skip_serializing_if = "Option::is_none")]
+    pub seed_ratio_limit: Option<f64>,
+    #[serde(rename = "seedRatioMode", skip_serializing_if = "Option::is_none")]
+    pub seed_ratio_mode: Option<SeedLimitMode>,
+    #[serde(rename = "seedIdleLimit", skip_serializing_if = "Option::is_none")]
+    pub seed_idle_limit: Option<i64>,
+    #[serde(rename = "seedIdleMode", skip_serializing_if = "Option::is_none")]
+    pub seed_idle_mode: Option<SeedLimitMode>,
+    #[serde(rename = "bandwidthPriority", skip_serializing_if = "Option::is_none")]
+    pub bandwidth_priority: Option<Priority>,
+    #[serde(rename = "peer-limit", skip_serializing_if = "Option::is_none")]
+    pub peer_limit: Option<i64>,
+    #[serde(rename = "queuePosition", skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<i64>,
+    /// Replaces all labels. Needs RPC version 16 (Transmission 3.0).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    /// The bandwidth group's name, or empty for none. Needs RPC version 17 (Transmission 4.0).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+/// Whether a torrent uses the session's seeding limit, its own, or none.
+#[derive(Debug, Clone, Copy, Default, Serialize_repr, Deserialize_repr, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SeedLimitMode {
+    #[default]
+    Global = 0,
+    Single = 1,
+    Unlimited = 2,
+}
+
+/// Per-torrent limits. Speeds are in kB/s and the idle limit in minutes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentLimits {
+    #[serde(alias = "download_limit")]
+    pub download_limit: i64,
+    #[serde(alias = "download_limited")]
+    pub download_limited: bool,
+    #[serde(alias = "upload_limit")]
+    pub upload_limit: i64,
+    #[serde(alias = "upload_limited")]
+    pub upload_limited: bool,
+    #[serde(alias = "honors_session_limits")]
+    pub honors_session_limits: bool,
+    #[serde(alias = "seed_ratio_limit")]
+    pub seed_ratio_limit: f64,
+    #[serde(alias = "seed_ratio_mode")]
+    pub seed_ratio_mode: SeedLimitMode,
+    #[serde(alias = "seed_idle_limit")]
+    pub seed_idle_limit: i64,
+    #[serde(alias = "seed_idle_mode")]
+    pub seed_idle_mode: SeedLimitMode,
+    #[serde(alias = "bandwidth_priority")]
+    pub bandwidth_priority: Priority,
+    #[serde(rename = "peer-limit", alias = "peer_limit")]
+    pub peer_limit: i64,
+    #[serde(alias = "queue_position")]
+    pub queue_position: i64,
+    /// The bandwidth group's name, or empty for none. Only reported by Transmission 4.0 and later.
+    #[serde(default)]
+    pub group: String,
+}
+
+impl TorrentLimits {
+    /// The `torrent-get` fields needed to fill this in.
+    pub const FIELDS: [&'static str; 13] = [
+        "downloadLimit",
+        "downloadLimited",
+        "uploadLimit",
+        "uploadLimited",
+        "honorsSessionLimits",
+        "seedRatioLimit",
+        "seedRatioMode",
+        "seedIdleLimit",
+        "seedIdleMode",
+        "bandwidthPriority",
+        "peer-limit",
+        "queuePosition",
+        "group",
+    ];
+}
+
+/// A bandwidth group caps the combined speed of the torrents in it. Speeds are in kB/s. Needs RPC
+/// version 17 (Transmission 4.0).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BandwidthGroup {
+    pub name: String,
+    #[serde(rename = "honorsSessionLimits", alias = "honors_session_limits")]
+    pub honors_session_limits: bool,
+    #[serde(
+        rename = "speed-limit-down-enabled",
+        alias = "speed_limit_down_enabled"
+    )]
+    pub speed_limit_down_enabled: bool,
+    #[serde(rename = "speed-limit-down", alias = "speed_limit_down")]
+    pub speed_limit_down: i64,
+    #[serde(rename = "speed-limit-up-enabled", alias = "speed_limit_up_enabled")]
+    pub speed_limit_up_enabled: bool,
+    #[serde(rename = "speed-limit-up", alias = "speed_limit_up")]
+    pub speed_limit_up: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupGetRequest {
+    /// Names of the groups to get, or empty for all of them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub group: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupGetResponse {
+    pub group: Vec<BandwidthGroup>,
+}
+
+/// Transmission expects `trackerReplace` as a flat list alternating ids and URLs.
+fn serialize_tracker_replace<S: serde::Serializer>(
+    replacements: &Option<Vec<(i64, String)>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let flat: Vec<Value> = replacements
+        .iter()
+        .flatten()
+        .flat_map(|(id, url)| [Value::from(*id), Value::from(url.as_str())])
+        .collect();
+    flat.serialize(serializer)
+}
+
+/// Transmission treats an empty file list as "all files", so never send one.
+fn non_empty(files: Vec<usize>) -> Option<Vec<usize>> {
+    if files.is_empty() {
+        None
+    } else {
+        Some(files)
+    }
+}
+
+impl TorrentSetRequest {
+    pub fn new(ids: Vec<i64>) -> Self {
+        TorrentSetRequest {
+            ids,
+            ..Default::default()
+        }
+    }
+
+    /// Downloads the files with these indices.
+    pub fn files_wanted(mut self, files: Vec<usize>) -> Self {
+        self.files_wanted = non_empty(files);
+        self
+    }
+
+    /// Skips the files with these indices.
+    pub fn files_unwanted(mut self, files: Vec<usize>) -> Self {
+        self.files_unwanted = non_empty(files);
+        self
+    }
+
+    pub fn tracker_list(mut self, tracker_list: String) -> Self {
+        self.tracker_list = Some(tracker_list);
+        self
+    }
+
+    pub fn tracker_add(mut self, urls: Vec<String>) -> Self {
+        self.tracker_add = Some(urls);
+        self
+    }
+
+    pub fn tracker_remove(mut self, tracker_ids: Vec<i64>) -> Self {
+        self.tracker_remove = Some(tracker_ids);
+        self
+    }
+
+    pub fn tracker_replace(mut self, replacements: Vec<(i64, String)>) -> Self {
+        self.tracker_replace = Some(replacements);
+        self
+    }
+
+    pub fn labels(mut self, labels: Vec<String>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Sets the limits that differ between `old` and `new`.
+    pub fn limits(mut self, old: &TorrentLimits, new: &TorrentLimits) -> Self {
+        macro_rules! diff {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if old.$field != new.$field {
+                        self.$field = Some(new.$field);
+                    }
+                )*
+            };
+        }
+        diff!(
+            download_limit,
+            download_limited,
+            upload_limit,
+            upload_limited,
+            honors_session_limits,
+            seed_ratio_limit,
+            seed_ratio_mode,
+            seed_idle_limit,
+            seed_idle_mode,
+            bandwidth_priority,
+            peer_limit,
+            queue_position,
+        );
+        if old.group != new.group {
+            self.group = Some(new.group.clone());
+        }
+        self
+    }
+
+    /// Whether anything besides the ids is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::new(self.ids.clone())
+    }
+
+    pub fn file_priority(mut self, priority: Priority, files: Vec<usize>) -> Self {
+        let files = non_empty(files);
+        match priority {
+            Priority::High => self.priority_high = files,
+            Priority::Normal => self.priority_normal = files,
+            Priority::Low => self.priority_low = files,
+        }
+        self
+    }
+}
+
+/// Actions that take nothing but a list of torrent ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TorrentAction {
+    Start,
+    /// Start immediately, bypassing the download queue.
+    StartNow,
+    Stop,
+    Verify,
+    /// Ask the trackers for more peers.
+    Reannounce,
+    QueueTop,
+    QueueUp,
+    QueueDown,
+    QueueBottom,
+}
+
+impl TorrentAction {
+    pub fn method(&self) -> &'static str {
+        match self {
+            TorrentAction::Start => "torrent-start",
+            TorrentAction::StartNow => "torrent-start-now",
+            TorrentAction::Stop => "torrent-stop",
+            TorrentAction::Verify => "torrent-verify",
+            TorrentAction::Reannounce => "torrent-reannounce",
+            TorrentAction::QueueTop => "queue-move-top",
+            TorrentAction::QueueUp => "queue-move-up",
+            TorrentAction::QueueDown => "queue-move-down",
+            TorrentAction::QueueBottom => "queue-move-bottom",
+        }
+    }
+}
+
+/// Bandwidth priority of a torrent, or download priority of a file.
+#[derive(Debug, Clone, Copy, Serialize_repr, Deserialize_repr, PartialEq, Eq)]
+#[repr(i8)]
+pub enum Priority {
+    Low = -1,
+    Normal = 0,
+    High = 1,
+}
+
+/// Arguments for `torrent-add`. Exactly one of `filename` or `metainfo` must be set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TorrentAddRequest {
+    /// A magnet link, or the URL or daemon-local path of a .torrent file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Base64-encoded contents of a .torrent file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metainfo: Option<String>,
+    #[serde(rename = "download-dir", skip_serializing_if = "Option::is_none")]
+    pub download_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+    #[serde(rename = "bandwidthPriority", skip_serializing_if = "Option::is_none")]
+    pub bandwidth_priority: Option<Priority>,
+    /// Indices of the files to download. Defaults to all files.
+    #[serde(rename = "files-wanted", skip_serializing_if = "Option::is_none")]
+    pub files_wanted: Option<Vec<usize>>,
+    /// Indices of the files to skip.
+    #[serde(rename = "files-unwanted", skip_serializing_if = "Option::is_none")]
+    pub files_unwanted: Option<Vec<usize>>,
+}
+
+impl TorrentAddRequest {
+    pub fn filename(filename: String) -> Self {
+        TorrentAddRequest {
+            filename: Some(filename),
+            ..Default::default()
+        }
+    }
+
+    pub fn metainfo(metainfo: String) -> Self {
+        TorrentAddRequest {
+            metainfo: Some(metainfo),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddedTorrent {
+    pub id: f64,
+    pub name: String,
+    #[serde(alias = "hash_string")]
+    pub hash_string: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentAddResponse {
+    #[serde(rename = "torrent-added", alias = "torrent_added")]
+    pub torrent_added: Option<AddedTorrent>,
+    #[serde(rename = "torrent-duplicate", alias = "torrent_duplicate")]
+    pub torrent_duplicate: Option<AddedTorrent>,
+}
+
+/// The outcome of `Client::replace_tracker_everywhere`.
+#[derive(Debug, Clone, Default)]
+pub struct TrackerReplacement {
+    /// How many torrents now use the new announce URL.
+    pub changed: usize,
+    /// Torrents that still use the old announce URL, and why.
+    pub failed: Vec<(i64, Error)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TorrentAddResult {
+    Added(AddedTorrent),
+    /// The daemon already had this torrent, so nothing was added.
+    Duplicate(AddedTorrent),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentFile {
+    pub name: String,
+    pub length: f64,
+    #[serde(alias = "bytes_completed")]
+    pub bytes_completed: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStat {
+    #[serde(alias = "bytes_completed")]
+    pub bytes_completed: f64,
+    pub wanted: bool,
+    pub priority: Priority,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Peer {
+    pub address: String,
+    pub port: i64,
+    #[serde(alias = "client_name")]
+    pub client_name: String,
+    /// Transmission's one-letter summary of the connection, see `PeerFlag`.
+    #[serde(alias = "flag_str")]
+    pub flag_str: String,
+    pub progress: f64,
+    #[serde(alias = "rate_to_client")]
+    pub rate_to_client: i64,
+    #[serde(alias = "rate_to_peer")]
+    pub rate_to_peer: i64,
+    #[serde(alias = "is_encrypted")]
+    pub is_encrypted: bool,
+    #[serde(alias = "is_incoming")]
+    pub is_incoming: bool,
+    #[serde(rename = "isUTP", alias = "is_utp")]
+    pub is_utp: bool,
+}
+
+impl Peer {
+    pub fn flags(&self) -> Vec<PeerFlag> {
+        self.flag_str
+            .chars()
+            .filter_map(PeerFlag::from_char)
+            .collect()
+    }
+}
+
+/// The letters Transmission uses in `Peer::flag_str`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerFlag {
+    OptimisticUnchoke,
+    Downloading,
+    /// We want to download, but the peer has choked us.
+    DownloadChoked,
+    Uploading,
+    /// The peer wants to download, but we have choked it.
+    UploadChoked,
+    /// The peer has unchoked us, but we are not interested.
+    PeerUnchoked,
+    /// We have unchoked the peer, but it is not interested.
+    ClientUnchoked,
+    Encrypted,
+    FromDht,
+    FromPex,
+    FromLpd,
+    Incoming,
+    Utp,
+}
+
+impl PeerFlag {
+    pub fn from_char(flag: char) -> Option<PeerFlag> {
+        match flag {
+            'O' => Some(PeerFlag::OptimisticUnchoke),
+            'D' => Some(PeerFlag::Downloading),
+            'd' => Some(PeerFlag::DownloadChoked),
+            'U' => Some(PeerFlag::Uploading),
+            'u' => Some(PeerFlag::UploadChoked),
+            'K' => Some(PeerFlag::PeerUnchoked),
+            '?' => Some(PeerFlag::ClientUnchoked),
+            'E' => Some(PeerFlag::Encrypted),
+            'H' => Some(PeerFlag::FromDht),
+            'X' => Some(PeerFlag::FromPex),
+            'L' => Some(PeerFlag::FromLpd),
+            'I' => Some(PeerFlag::Incoming),
+            'T' => Some(PeerFlag::Utp),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            PeerFlag::OptimisticUnchoke => "Optimistic unchoke",
+            PeerFlag::Downloading => "Downloading from this peer",
+            PeerFlag::DownloadChoked => "We would download from this peer if they'd let us",
+            PeerFlag::Uploading => "Uploading to peer",
+            PeerFlag::UploadChoked => "We would upload to this peer if they'd ask",
+            PeerFlag::PeerUnchoked => "Peer has unchoked us, but we're not interested",
+            PeerFlag::ClientUnchoked => "We unchoked this peer, but they're not interested",
+            PeerFlag::Encrypted => "Encrypted connection",
+            PeerFlag::FromDht => "Peer was found through DHT",
+            PeerFlag::FromPex => "Peer was found through peer exchange (PEX)",
+            PeerFlag::FromLpd => "Peer was found through local peer discovery (LPD)",
+            PeerFlag::Incoming => "Peer connected to us",
+            PeerFlag::Utp => "uTP connection",
+        }
+    }
+}
+
+/// How many of the connected peers were found through each source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeersFrom {
+    #[serde(alias = "from_cache")]
+    pub from_cache: i64,
+    #[serde(alias = "from_dht")]
+    pub from_dht: i64,
+    #[serde(alias = "from_incoming")]
+    pub from_incoming: i64,
+    #[serde(alias = "from_lpd")]
+    pub from_lpd: i64,
+    #[serde(alias = "from_ltep")]
+    pub from_ltep: i64,
+    #[serde(alias = "from_pex")]
+    pub from_pex: i64,
+    #[serde(alias = "from_tracker")]
+    pub from_tracker: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tracker {
+    pub id: i64,
+    pub announce: String,
+    pub scrape: String,
+    pub tier: i64,
+}
+
+/// Formats trackers as a `trackerList`: one announce URL per line, with a blank line between
+/// tiers.
+pub fn tracker_list(trackers: &[Tracker]) -> String {
+    let mut trackers: Vec<&Tracker> = trackers.iter().collect();
+    trackers.sort_by_key(|tracker| tracker.tier);
+    let mut list = String::new();
+    let mut tier = None;
+    for tracker in trackers {
+        if tier.is_some() && tier != Some(tracker.tier) {
+            list.push('\n');
+        }
+        tier = Some(tracker.tier);
+        list.push_str(&tracker.announce);
+        list.push('\n');
+    }
+    list
+}
+
+#[derive(Debug, Clone, Copy, Serialize_repr, Deserialize_repr, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TrackerState {
+    Inactive = 0,
+    Waiting = 1,
+    Queued = 2,
+    Active = 3,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackerStat {
+    pub id: i64,
+    pub announce: String,
+    pub host: String,
+    pub tier: i64,
+    #[serde(alias = "is_backup")]
+    pub is_backup: bool,
+    #[serde(alias = "announce_state")]
+    pub announce_state: TrackerState,
+    #[serde(alias = "has_announced")]
+    pub has_announced: bool,
+    #[serde(alias = "last_announce_time")]
+    pub last_announce_time: i64,
+    #[serde(alias = "last_announce_succeeded")]
+    pub last_announce_succeeded: bool,
+    #[serde(alias = "last_announce_timed_out")]
+    pub last_announce_timed_out: bool,
+    #[serde(alias = "last_announce_result")]
+    pub last_announce_result: String,
+    #[serde(alias = "last_announce_peer_count")]
+    pub last_announce_peer_count: i64,
+    /// Unix timestamp, or 0 if no announce is scheduled.
+    #[serde(alias = "next_announce_time")]
+    pub next_announce_time: i64,
+    /// -1 if the tracker has not reported it.
+    #[serde(alias = "seeder_count")]
+    pub seeder_count: i64,
+    #[serde(alias = "leecher_count")]
+    pub leecher_count: i64,
+    #[serde(alias = "download_count")]
+    pub download_count: i64,
+}
+
+/// Everything the torrent detail page shows about a single torrent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentDetail {
+    pub id: f64,
+    pub name: String,
+    #[serde(alias = "hash_string")]
+    pub hash_string: String,
+    pub creator: String,
+    /// Unix timestamp, or 0 if the torrent does not say when it was created.
+    #[serde(alias = "date_created")]
+    pub date_created: i64,
+    pub comment: String,
+    #[serde(alias = "download_dir")]
+    pub download_dir: String,
+    #[serde(alias = "added_date")]
+    pub added_date: i64,
+    /// Unix timestamp, or 0 if the torrent has not finished downloading.
+    #[serde(alias = "done_date")]
+    pub done_date: i64,
+    #[serde(alias = "upload_ratio")]
+    pub upload_ratio: f64,
+    pub eta: f64,
+    /// 0 if there is no error, otherwise see `error_string`.
+    pub error: i64,
+    #[serde(alias = "error_string")]
+    pub error_string: String,
+    pub status: TorrentStatus,
+    #[serde(alias = "percent_done")]
+    pub percent_done: f64,
+    #[serde(alias = "size_when_done")]
+    pub size_when_done: f64,
+    #[serde(alias = "total_size")]
+    pub total_size: f64,
+    #[serde(alias = "downloaded_ever")]
+    pub downloaded_ever: f64,
+    #[serde(alias = "uploaded_ever")]
+    pub uploaded_ever: f64,
+    #[serde(alias = "corrupt_ever")]
+    pub corrupt_ever: f64,
+    #[serde(alias = "piece_count")]
+    pub piece_count: i64,
+    #[serde(alias = "piece_size")]
+    pub piece_size: f64,
+    pub pieces: String,
+    #[serde(alias = "is_private")]
+    pub is_private: bool,
+    pub files: Vec<TorrentFile>,
+    /// Same order as `files`.
+    #[serde(alias = "file_stats")]
+    pub file_stats: Vec<FileStat>,
+    pub peers: Vec<Peer>,
+    #[serde(alias = "peers_from")]
+    pub peers_from: PeersFrom,
+    #[serde(alias = "peers_connected")]
+    pub peers_connected: i64,
+    #[serde(alias = "peers_getting_from_us")]
+    pub peers_getting_from_us: i64,
+    #[serde(alias = "peers_sending_to_us")]
+    pub peers_sending_to_us: i64,
+    pub trackers: Vec<Tracker>,
+    #[serde(alias = "tracker_stats")]
+    pub tracker_stats: Vec<TrackerStat>,
+    #[serde(flatten)]
+    pub limits: TorrentLimits,
+}
+
+impl TorrentDetail {
+    /// Applies a rename to the file list, and to the torrent's name if its root was renamed.
+    pub fn apply_rename(&mut self, renamed: &RenamedPath) {
+        let new_path = match renamed.path.rsplit_once('/') {
+            Some((parent, _)) => format!("{parent}/{}", renamed.name),
+            None => renamed.name.clone(),
+        };
+        let prefix = format!("{}/", renamed.path);
+        for file in &mut self.files {
+            if file.name == renamed.path {
+                file.name = new_path.clone();
+            } else if let Some(rest) = file.name.strip_prefix(&prefix) {
+                file.name = format!("{new_path}/{rest}");
+            }
+        }
+        if !renamed.path.contains('/') {
+            self.name = renamed.name.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentDetailResponse {
+    pub torrents: Vec<TorrentDetail>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentTrackers {
+    pub id: f64,
+    pub trackers: Vec<Tracker>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentTrackersResponse {
+    pub torrents: Vec<TorrentTrackers>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TorrentSummaryResponse {
     pub torrents: Vec<TorrentSummary>,
+    /// Ids of torrents removed in the last minute. Only sent for `recently-active` requests.
+    #[serde(default)]
+    pub removed: Vec<i64>,
+}
+
+/// Turns a table-format torrent list into the usual list of objects. Anything else is returned
+/// as it is.
+fn table_to_objects(torrents: Value) -> Result<Value> {
+    let Value::Array(rows) = torrents else {
+        return Ok(torrents);
+    };
+    if !matches!(rows.first(), Some(Value::Array(_))) {
+        return Ok(Value::Array(rows));
+    }
+    let mut rows = rows.into_iter();
+    let fields: Vec<String> = serde_json::from_value(rows.next().unwrap_or_default())?;
+    let objects = rows
+        .map(|row| match row {
+            Value::Array(values) => Ok(Value::Object(fields.iter().cloned().zip(values).collect())),
+            _ => Err(Error::Decode(
+                "torrent table row is not an array".to_string(),
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Value::Array(objects))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,8 +1280,16 @@ pub struct ResponseNoArgs {
 pub enum RequestArgs {
     GetSessionArgs(GetSessionRequest),
     GetTorrentArgs(GetTorrentRequest),
-    TorrentStopArgs(TorrentActionRequest),
-    TorrentStartArgs(TorrentActionRequest),
+    TorrentActionArgs(TorrentActionRequest),
+    TorrentAddArgs(TorrentAddRequest),
+    TorrentRemoveArgs(TorrentRemoveRequest),
+    TorrentSetLocationArgs(TorrentSetLocationRequest),
+    TorrentRenamePathArgs(TorrentRenamePathRequest),
+    FreeSpaceArgs(FreeSpaceRequest),
+    TorrentSetArgs(TorrentSetRequest),
+    SessionSetArgs(SessionSetRequest),
+    GroupGetArgs(GroupGetRequest),
+    GroupSetArgs(BandwidthGroup),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -151,63 +1299,96 @@ pub enum ResponseArgs {
     GetTorrentRes(GetTorrentResponse),
 }
 
+/// A password that is never printed, since `Client` and `ClientBuilder` are traced with `Debug`.
+#[derive(Clone)]
+struct Password(String);
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Password(..)")
+    }
+}
+
 #[derive(Debug)]
 pub struct Client {
     tm_url: String,
-    session_id: Mutex<Arc<String>>,
+    http: HttpClient,
+    username: Option<String>,
+    password: Option<Password>,
+    session_id: Mutex<String>,
+    /// The daemon's RPC version and the protocol picked for it, once known.
+    rpc_version: Mutex<Option<(i64, Protocol)>>,
+    /// The `id` of the next JSON-RPC request.
+    next_id: AtomicU64,
 }
 
 impl Client {
-    #[tracing::instrument]
-    pub async fn send(&self, request: &Request) -> color_eyre::Result<Value> {
-        let mut headers = HeaderMap::new();
-        {
-            let session_id = self.session_id.lock();
-            headers.insert("X-Transmission-Session-Id", session_id.parse().unwrap());
-        }
+    /// Sends a request in whichever protocol the daemon speaks, and returns the response in the
+    /// legacy format.
+    #[tracing::instrument(skip(request), fields(method = %request.method))]
+    pub async fn send(&self, request: &Request) -> Result<Value> {
+        let protocol = self.protocol().await?;
+        self.send_as(request, protocol).await
+    }
 
-        let mut response = HttpClient::builder()
-            .redirect(Policy::default())
-            .timeout(Duration::from_secs(5))
-            .default_headers(headers)
-            .build()?
-            .post(&self.tm_url)
-            .body(serde_json::to_string(&request)?)
-            .send()
-            .await?;
+    async fn send_as(&self, request: &Request, protocol: Protocol) -> Result<Value> {
+        let body = match protocol {
+            Protocol::Legacy => serde_json::to_value(request)?,
+            Protocol::JsonRpc => {
+                json_rpc::request(request, self.next_id.fetch_add(1, Ordering::Relaxed))?
+            }
+        };
+        let mut response = self.post(&body).await?;
 
+        // Transmission rejects requests with a stale session id, handing out the current one in
+        // the 409 response. Remember it and retry once.
         if response.status() == StatusCode::CONFLICT {
-            let header: String = response
+            let session_id = response
                 .headers()
-                .get("x-transmission-session-id")
-                .unwrap()
-                .clone()
-                .to_str()
-                .unwrap()
+                .get(SESSION_ID_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .ok_or(Error::MissingSessionId)?
                 .to_string();
-            *self.session_id.lock() = Arc::new(header);
+            *self.session_id.lock() = session_id;
+            response = self.post(&body).await?;
+        }
 
-            let mut headers = HeaderMap::new();
-            {
-                let session_id = self.session_id.lock();
-                let session_id_parsed = session_id.parse()?;
-                headers.insert("X-Transmission-Session-Id", session_id_parsed);
-            }
-            response = HttpClient::builder()
-                .redirect(Policy::default())
-                .timeout(Duration::from_secs(5))
-                .default_headers(headers)
-                .build()?
-                .post(&self.tm_url)
-                .body(serde_json::to_string(&request)?)
-                .send()
-                .await?;
+        match response.status() {
+            StatusCode::UNAUTHORIZED => return Err(Error::Unauthorized),
+            StatusCode::CONFLICT => return Err(Error::SessionConflict),
+            status if !status.is_success() => return Err(Error::Http(status.to_string())),
+            _ => {}
+        }
+
+        let result: Value = response.json().await?;
+        let result = match protocol {
+            Protocol::Legacy => result,
+            Protocol::JsonRpc => json_rpc::response(result)?,
+        };
+        match result.get("result").and_then(Value::as_str) {
+            Some("success") => Ok(result),
+            Some(message) => Err(Error::Rpc(message.to_string())),
+            None => Err(Error::Decode("response has no result".to_string())),
         }
+    }
 
-        let result = response.json().await?;
-        Ok(result)
+    async fn post(&self, body: &Value) -> Result<reqwest::Response> {
+        let session_id = self.session_id.lock().clone();
+        let mut builder = self
+            .http
+            .post(&self.tm_url)
+            .header(SESSION_ID_HEADER, session_id)
+            .json(body);
+        if let Some(username) = &self.username {
+            let password = self.password.as_ref().map(|p| p.0.as_str());
+            builder = builder.basic_auth(username, password);
+        }
+        let response = builder.send().await?;
+        Ok(response)
     }
 
+    /// Always uses the legacy protocol, which every daemon understands, so that it can be used to
+    /// find out which protocol to use.
     #[tracing::instrument]
     pub async fn session_get(&self, fields: Vec<String>) -> Result<Response<GetSessionResponse>> {
         let request = Request {
@@ -215,11 +1396,103 @@ impl Client {
             arguments: Some(RequestArgs::GetSessionArgs(GetSessionRequest { fields })),
             tag: None,
         };
-        let res = self.send(&request).await?;
+        let res = self.send_as(&request, Protocol::Legacy).await?;
         let response: Response<GetSessionResponse> = serde_json::from_value(res)?;
         Ok(response)
     }
 
+    /// The daemon's RPC version and protocol, fetched once and then cached.
+    async fn detect_rpc_version(&self) -> Result<(i64, Protocol)> {
+        if let Some(detected) = *self.rpc_version.lock() {
+            return Ok(detected);
+        }
+        let fields = vec!["rpc-version".to_string(), "rpc-version-semver".to_string()];
+        let response = self.session_get(fields).await?.arguments;
+        let rpc_version = response
+            .rpc_version
+            .ok_or_else(|| Error::Decode("session-get did not return rpc-version".to_string()))?;
+        let protocol = Protocol::detect(response.rpc_version_semver.as_deref());
+        tracing::info!(rpc_version, ?protocol, "Detected Transmission RPC version");
+        let detected = (rpc_version, protocol);
+        *self.rpc_version.lock() = Some(detected);
+        Ok(detected)
+    }
+
+    #[tracing::instrument]
+    pub async fn rpc_version(&self) -> Result<i64> {
+        Ok(self.detect_rpc_version().await?.0)
+    }
+
+    #[tracing::instrument]
+    pub async fn protocol(&self) -> Result<Protocol> {
+        Ok(self.detect_rpc_version().await?.1)
+    }
+
+    #[tracing::instrument]
+    pub async fn session_settings(&self) -> Result<SessionSettings> {
+        self.session_settings_fields(Vec::new()).await
+    }
+
+    /// Only the speed limits and turtle mode, which change more often than the other settings.
+    /// Everything else is left at its default, so merge it with `copy_speed_limits`.
+    #[tracing::instrument]
+    pub async fn session_speed_limits(&self) -> Result<SessionSettings> {
+        let fields = SessionSettings::SPEED_LIMIT_FIELDS
+            .iter()
+            .map(|field| field.to_string())
+            .collect();
+        self.session_settings_fields(fields).await
+    }
+
+    async fn session_settings_fields(&self, fields: Vec<String>) -> Result<SessionSettings> {
+        let request = Request {
+            method: "session-get".to_string(),
+            arguments: Some(RequestArgs::GetSessionArgs(GetSessionRequest { fields })),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: Response<SessionSettings> = serde_json::from_value(res)?;
+        Ok(response.arguments)
+    }
+
+    #[tracing::instrument]
+    pub async fn session_set(&self, args: SessionSetRequest) -> Result<ResponseNoArgs> {
+        let request = Request {
+            method: "session-set".to_string(),
+            arguments: Some(RequestArgs::SessionSetArgs(args)),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: ResponseNoArgs = serde_json::from_value(res)?;
+        Ok(response)
+    }
+
+    /// Gets the bandwidth groups with these names, or every group if `names` is empty.
+    #[tracing::instrument]
+    pub async fn group_get(&self, names: Vec<String>) -> Result<Vec<BandwidthGroup>> {
+        let request = Request {
+            method: "group-get".to_string(),
+            arguments: Some(RequestArgs::GroupGetArgs(GroupGetRequest { group: names })),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: Response<GroupGetResponse> = serde_json::from_value(res)?;
+        Ok(response.arguments.group)
+    }
+
+    /// Creates a bandwidth group, or replaces the settings of the group with the same name.
+    #[tracing::instrument]
+    pub async fn group_set(&self, group: BandwidthGroup) -> Result<ResponseNoArgs> {
+        let request = Request {
+            method: "group-set".to_string(),
+            arguments: Some(RequestArgs::GroupSetArgs(group)),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: ResponseNoArgs = serde_json::from_value(res)?;
+        Ok(response)
+    }
+
     #[tracing::instrument]
     pub async fn session_stats(&self) -> Result<Response<SessionStats>> {
         let request = Request {
@@ -239,6 +1512,7 @@ impl Client {
             arguments: Some(RequestArgs::GetTorrentArgs(GetTorrentRequest {
                 ids: None,
                 fields,
+                format: None,
             })),
             tag: None,
         };
@@ -247,11 +1521,17 @@ impl Client {
         Ok(response)
     }
 
+    /// Gets the torrents shown in lists: every torrent if `ids` is `None`. Uses the compact table
+    /// format when the daemon supports it.
     #[tracing::instrument]
-    pub async fn torrent_summary(&self) -> Result<Response<TorrentSummaryResponse>> {
+    pub async fn torrent_summary(
+        &self,
+        ids: Option<TorrentIds>,
+    ) -> Result<Response<TorrentSummaryResponse>> {
         let fields = vec![
             "id",
             "name",
+            "downloadDir",
             "percentComplete",
             "status",
             "eta",
@@ -264,26 +1544,99 @@ impl Client {
             "peersSendingToUs",
             "rateDownload",
             "rateUpload",
+            "uploadRatio",
+            "queuePosition",
+            "error",
+            "trackers",
+            "labels",
         ];
         let request = Request {
             method: "torrent-get".to_string(),
             arguments: Some(RequestArgs::GetTorrentArgs(GetTorrentRequest {
-                ids: None,
+                ids,
                 fields: fields.into_iter().map(|f| f.to_string()).collect(),
+                format: (self.rpc_version().await? >= 16).then_some(TorrentFormat::Table),
             })),
             tag: None,
         };
-        let res = self.send(&request).await?;
+        let mut res = self.send(&request).await?;
+        if let Some(torrents) = res.pointer_mut("/arguments/torrents") {
+            *torrents = table_to_objects(torrents.take())?;
+        }
         let response: Response<TorrentSummaryResponse> = serde_json::from_value(res)?;
         Ok(response)
     }
 
     #[tracing::instrument]
-    pub async fn torrent_action(&self, action: String, id: i64) -> Result<ResponseNoArgs> {
+    pub async fn torrent_detail(&self, id: i64) -> Result<TorrentDetail> {
+        let fields = vec![
+            "id",
+            "name",
+            "hashString",
+            "creator",
+            "dateCreated",
+            "comment",
+            "downloadDir",
+            "addedDate",
+            "doneDate",
+            "uploadRatio",
+            "eta",
+            "error",
+            "errorString",
+            "status",
+            "percentDone",
+            "sizeWhenDone",
+            "totalSize",
+            "downloadedEver",
+            "uploadedEver",
+            "corruptEver",
+            "pieceCount",
+            "pieceSize",
+            "pieces",
+            "isPrivate",
+            "files",
+            "fileStats",
+            "peers",
+            "peersFrom",
+            "peersConnected",
+            "peersGettingFromUs",
+            "peersSendingToUs",
+            "trackers",
+            "trackerStats",
+        ];
         let request = Request {
-            method: format!("torrent-{}", action),
-            arguments: Some(RequestArgs::TorrentStopArgs(TorrentActionRequest {
-                ids: Some(vec![id]),
+            method: "torrent-get".to_string(),
+            arguments: Some(RequestArgs::GetTorrentArgs(GetTorrentRequest {
+                ids: Some(TorrentIds::List(vec![id])),
+                fields: fields
+                    .into_iter()
+                    .chain(TorrentLimits::FIELDS)
+                    .map(|f| f.to_string())
+                    .collect(),
+                format: None,
+            })),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: Response<TorrentDetailResponse> = serde_json::from_value(res)?;
+        response
+            .arguments
+            .torrents
+            .into_iter()
+            .next()
+            .ok_or(Error::TorrentNotFound(id))
+    }
+
+    #[tracing::instrument]
+    pub async fn torrent_action(
+        &self,
+        action: TorrentAction,
+        ids: Vec<i64>,
+    ) -> Result<ResponseNoArgs> {
+        let request = Request {
+            method: action.method().to_string(),
+            arguments: Some(RequestArgs::TorrentActionArgs(TorrentActionRequest {
+                ids: Some(ids),
             })),
             tag: None,
         };
@@ -291,29 +1644,529 @@ impl Client {
         let response: ResponseNoArgs = serde_json::from_value(res)?;
         Ok(response)
     }
+
+    pub async fn torrent_start(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::Start, ids).await
+    }
+
+    pub async fn torrent_start_now(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::StartNow, ids).await
+    }
+
+    pub async fn torrent_stop(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::Stop, ids).await
+    }
+
+    pub async fn torrent_verify(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::Verify, ids).await
+    }
+
+    pub async fn torrent_reannounce(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::Reannounce, ids).await
+    }
+
+    pub async fn queue_move_top(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::QueueTop, ids).await
+    }
+
+    pub async fn queue_move_up(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::QueueUp, ids).await
+    }
+
+    pub async fn queue_move_down(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::QueueDown, ids).await
+    }
+
+    pub async fn queue_move_bottom(&self, ids: Vec<i64>) -> Result<ResponseNoArgs> {
+        self.torrent_action(TorrentAction::QueueBottom, ids).await
+    }
+
+    #[tracing::instrument]
+    pub async fn torrent_set(&self, args: TorrentSetRequest) -> Result<ResponseNoArgs> {
+        let request = Request {
+            method: "torrent-set".to_string(),
+            arguments: Some(RequestArgs::TorrentSetArgs(args)),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: ResponseNoArgs = serde_json::from_value(res)?;
+        Ok(response)
+    }
+
+    /// Replaces a torrent's trackers with `tiers`, each a list of announce URLs. Daemons older
+    /// than Transmission 4.0 cannot set tiers, so there the new URLs are added to the last tier.
+    #[tracing::instrument]
+    pub async fn torrent_set_trackers(
+        &self,
+        id: i64,
+        current: &[Tracker],
+        tiers: Vec<Vec<String>>,
+    ) -> Result<ResponseNoArgs> {
+        let request = TorrentSetRequest::new(vec![id]);
+        if self.rpc_version().await? >= 17 {
+            let tracker_list = tiers
+                .iter()
+                .map(|tier| tier.join("\n"))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            return self.torrent_set(request.tracker_list(tracker_list)).await;
+        }
+
+        let urls: Vec<String> = tiers.into_iter().flatten().collect();
+        let removed: Vec<i64> = current
+            .iter()
+            .filter(|tracker| !urls.contains(&tracker.announce))
+            .map(|tracker| tracker.id)
+            .collect();
+        let added: Vec<String> = urls
+            .into_iter()
+            .filter(|url| !current.iter().any(|tracker| &tracker.announce == url))
+            .collect();
+        let mut response = None;
+        if !removed.is_empty() {
+            let request = TorrentSetRequest::new(vec![id]).tracker_remove(removed);
+            response = Some(self.torrent_set(request).await?);
+        }
+        if !added.is_empty() {
+            response = Some(self.torrent_set(request.tracker_add(added)).await?);
+        }
+        match response {
+            Some(response) => Ok(response),
+            None => Ok(ResponseNoArgs {
+                result: "success".to_string(),
+                tag: None,
+            }),
+        }
+    }
+
+    /// Replaces the announce URL `old` with `new` in every torrent that uses it, e.g. after a
+    /// private tracker changed its passkey. A torrent that can't be changed doesn't stop the
+    /// others; it is reported in `failed` instead.
+    #[tracing::instrument]
+    pub async fn replace_tracker_everywhere(
+        &self,
+        old: &str,
+        new: &str,
+    ) -> Result<TrackerReplacement> {
+        let request = Request {
+            method: "torrent-get".to_string(),
+            arguments: Some(RequestArgs::GetTorrentArgs(GetTorrentRequest {
+                ids: None,
+                fields: vec!["id".to_string(), "trackers".to_string()],
+                format: None,
+            })),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: Response<TorrentTrackersResponse> = serde_json::from_value(res)?;
+        let use_tracker_list = self.rpc_version().await? >= 17;
+
+        let mut replacement = TrackerReplacement::default();
+        for torrent in response.arguments.torrents {
+            let Some(tracker) = torrent.trackers.iter().find(|t| t.announce == old) else {
+                continue;
+            };
+            let request = TorrentSetRequest::new(vec![torrent.id as i64]);
+            let request = if use_tracker_list {
+                let trackers: Vec<Tracker> = torrent
+                    .trackers
+                    .iter()
+                    .map(|t| Tracker {
+                        announce: if t.announce == old {
+                            new.to_string()
+                        } else {
+                            t.announce.clone()
+                        },
+                        ..t.clone()
+                    })
+                    .collect();
+                request.tracker_list(tracker_list(&trackers))
+            } else {
+                request.tracker_replace(vec![(tracker.id, new.to_string())])
+            };
+            match self.torrent_set(request).await {
+                Ok(_) => replacement.changed += 1,
+                Err(e) => replacement.failed.push((torrent.id as i64, e)),
+            }
+        }
+        Ok(replacement)
+    }
+
+    /// Removes torrents from Transmission, and their downloaded files if `delete_local_data` is set.
+    #[tracing::instrument]
+    pub async fn torrent_remove(
+        &self,
+        ids: Vec<i64>,
+        delete_local_data: bool,
+    ) -> Result<ResponseNoArgs> {
+        let request = Request {
+            method: "torrent-remove".to_string(),
+            arguments: Some(RequestArgs::TorrentRemoveArgs(TorrentRemoveRequest {
+                ids,
+                delete_local_data,
+            })),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: ResponseNoArgs = serde_json::from_value(res)?;
+        Ok(response)
+    }
+
+    /// Points torrents at `location`, moving their data there first if `move_data` is set.
+    #[tracing::instrument]
+    pub async fn torrent_set_location(
+        &self,
+        ids: Vec<i64>,
+        location: String,
+        move_data: bool,
+    ) -> Result<ResponseNoArgs> {
+        let request = Request {
+            method: "torrent-set-location".to_string(),
+            arguments: Some(RequestArgs::TorrentSetLocationArgs(
+                TorrentSetLocationRequest {
+                    ids,
+                    location,
+                    move_data,
+                },
+            )),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: ResponseNoArgs = serde_json::from_value(res)?;
+        Ok(response)
+    }
+
+    /// Renames the file or folder at `path` within a torrent to `name`. Renaming the top-level
+    /// path renames the torrent itself.
+    #[tracing::instrument]
+    pub async fn torrent_rename_path(
+        &self,
+        id: i64,
+        path: String,
+        name: String,
+    ) -> Result<RenamedPath> {
+        let request = Request {
+            method: "torrent-rename-path".to_string(),
+            arguments: Some(RequestArgs::TorrentRenamePathArgs(
+                TorrentRenamePathRequest {
+                    ids: vec![id],
+                    path,
+                    name,
+                },
+            )),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: Response<RenamedPath> = serde_json::from_value(res)?;
+        Ok(response.arguments)
+    }
+
+    /// How much space is free at `path` on the machine running Transmission.
+    #[tracing::instrument]
+    pub async fn free_space(&self, path: String) -> Result<FreeSpace> {
+        let request = Request {
+            method: "free-space".to_string(),
+            arguments: Some(RequestArgs::FreeSpaceArgs(FreeSpaceRequest { path })),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: Response<FreeSpace> = serde_json::from_value(res)?;
+        Ok(response.arguments)
+    }
+
+    #[tracing::instrument(skip(args), fields(filename = ?args.filename))]
+    pub async fn torrent_add(&self, args: TorrentAddRequest) -> Result<TorrentAddResult> {
+        let request = Request {
+            method: "torrent-add".to_string(),
+            arguments: Some(RequestArgs::TorrentAddArgs(args)),
+            tag: None,
+        };
+        let res = self.send(&request).await?;
+        let response: Response<TorrentAddResponse> = serde_json::from_value(res)?;
+        match response.arguments {
+            TorrentAddResponse {
+                torrent_added: Some(torrent),
+                ..
+            } => Ok(TorrentAddResult::Added(torrent)),
+            TorrentAddResponse {
+                torrent_duplicate: Some(torrent),
+                ..
+            } => Ok(TorrentAddResult::Duplicate(torrent)),
+            _ => Err(Error::Decode(
+                "torrent-add response has neither torrent-added nor torrent-duplicate".to_string(),
+            )),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ClientBuilder {
     tm_url: Option<String>,
+    username: Option<String>,
+    password: Option<Password>,
+    timeout: Duration,
+    root_certificates: Vec<reqwest::Certificate>,
+    accept_invalid_certs: bool,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ClientBuilder {
     #[tracing::instrument]
     pub fn new() -> Self {
-        Self { tm_url: None }
+        Self {
+            tm_url: None,
+            username: None,
+            password: None,
+            timeout: Duration::from_secs(5),
+            root_certificates: Vec::new(),
+            accept_invalid_certs: false,
+        }
     }
     #[tracing::instrument]
     pub fn transmission_url(mut self, tm_url: String) -> Self {
         self.tm_url = Some(tm_url);
         self
     }
+    /// Username for HTTP Basic authentication, needed when `rpc-authentication-required` is set.
+    #[tracing::instrument]
+    pub fn username(mut self, username: String) -> Self {
+        self.username = Some(username);
+        self
+    }
+    #[tracing::instrument(skip(password))]
+    pub fn password(mut self, password: String) -> Self {
+        self.password = Some(Password(password));
+        self
+    }
+    /// Timeout for each RPC request, including the session-id retry.
+    #[tracing::instrument]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Trusts an additional PEM-encoded CA certificate for HTTPS RPC endpoints.
+    #[tracing::instrument(skip(pem))]
+    pub fn root_certificate(mut self, pem: &[u8]) -> Result<Self> {
+        let certificate =
+            reqwest::Certificate::from_pem(pem).map_err(|e| Error::Tls(e.to_string()))?;
+        self.root_certificates.push(certificate);
+        Ok(self)
+    }
+    /// Accepts any certificate, e.g. a self-signed one. This disables protection against
+    /// man-in-the-middle attacks.
+    #[tracing::instrument]
+    pub fn accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
     #[tracing::instrument]
-    pub fn build(self) -> Result<Client, Box<dyn Error>> {
-        let tm_url = self.tm_url.expect("Expected a URL");
+    pub fn build(self) -> Result<Client> {
+        let tm_url = self
+            .tm_url
+            .ok_or_else(|| Error::InvalidUrl("no URL was given".to_string()))?;
+        match reqwest::Url::parse(&tm_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(_) => return Err(Error::InvalidUrl(format!("{tm_url} is not an http(s) URL"))),
+            Err(e) => return Err(Error::InvalidUrl(format!("{tm_url}: {e}"))),
+        }
+        let mut http = HttpClient::builder()
+            .redirect(Policy::default())
+            .timeout(self.timeout)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        for certificate in self.root_certificates {
+            http = http.add_root_certificate(certificate);
+        }
         Ok(Client {
             tm_url,
-            session_id: Mutex::new(Arc::new("unknown".to_string())),
+            http: http.build().map_err(|e| Error::Tls(e.to_string()))?,
+            username: self.username,
+            password: self.password,
+            session_id: Mutex::new("unknown".to_string()),
+            rpc_version: Mutex::new(None),
+            next_id: AtomicU64::new(1),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn builder_rejects_missing_and_invalid_urls() {
+        assert!(matches!(
+            ClientBuilder::new().build(),
+            Err(Error::InvalidUrl(_))
+        ));
+        for url in ["not a url", "ftp://localhost:9091/transmission/rpc"] {
+            let result = ClientBuilder::new()
+                .transmission_url(url.to_string())
+                .build();
+            assert!(matches!(result, Err(Error::InvalidUrl(_))), "{url}");
+        }
+        assert!(ClientBuilder::new()
+            .transmission_url("http://localhost:9091/transmission/rpc".to_string())
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn tables_become_objects() {
+        let table = json!([["id", "name"], [1, "Debian"], [2, "Fedora"]]);
+        assert_eq!(
+            table_to_objects(table).unwrap(),
+            json!([{ "id": 1, "name": "Debian" }, { "id": 2, "name": "Fedora" }])
+        );
+    }
+
+    #[test]
+    fn tables_with_only_a_header_are_empty() {
+        let table = json!([["id", "name"]]);
+        assert_eq!(table_to_objects(table).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn objects_are_kept() {
+        let objects = json!([{ "id": 1 }]);
+        assert_eq!(table_to_objects(objects.clone()).unwrap(), objects);
+        assert_eq!(table_to_objects(json!([])).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn table_rows_must_be_arrays() {
+        let table = json!([["id", "name"], { "id": 1 }]);
+        assert!(matches!(table_to_objects(table), Err(Error::Decode(_))));
+    }
+
+    #[test]
+    fn json_rpc_names_are_aliases() {
+        let settings: SessionSettings = serde_json::from_value(json!({
+            "speed_limit_down": 100,
+            "seed_ratio_limit": 2.0,
+            "peer_port": 51413,
+        }))
+        .unwrap();
+        assert_eq!(settings.speed_limit_down, 100);
+        assert_eq!(settings.seed_ratio_limit, 2.0);
+        assert_eq!(settings.peer_port, 51413);
+
+        let peer: Peer = serde_json::from_value(json!({
+            "address": "10.0.0.2",
+            "port": 51413,
+            "client_name": "Transmission 4.1.0",
+            "flag_str": "TE",
+            "progress": 0.5,
+            "rate_to_client": 0,
+            "rate_to_peer": 10,
+            "is_encrypted": true,
+            "is_incoming": false,
+            "is_utp": true,
+        }))
+        .unwrap();
+        assert!(peer.is_utp);
+        assert_eq!(peer.client_name, "Transmission 4.1.0");
+    }
+
+    #[test]
+    fn json_rpc_tables_decode() {
+        let table = json!([
+            [
+                "id",
+                "name",
+                "download_dir",
+                "percent_complete",
+                "percent_done",
+                "status",
+                "size_when_done",
+                "piece_count",
+                "pieces",
+                "eta",
+                "peers_connected",
+                "peers_getting_from_us",
+                "peers_sending_to_us",
+                "rate_download",
+                "rate_upload",
+                "upload_ratio",
+                "queue_position",
+                "error",
+                "trackers"
+            ],
+            [
+                1,
+                "Debian",
+                "/downloads",
+                1.0,
+                1.0,
+                6,
+                1024.0,
+                1,
+                "",
+                -1,
+                2,
+                1,
+                0,
+                0,
+                512,
+                0.5,
+                0,
+                0,
+                []
+            ],
+        ]);
+        let torrents: Vec<TorrentSummary> =
+            serde_json::from_value(table_to_objects(table).unwrap()).unwrap();
+        assert_eq!(torrents[0].download_dir, "/downloads");
+        assert_eq!(torrents[0].rate_upload, 512);
+    }
+
+    #[test]
+    fn flattened_limits_take_json_rpc_names() {
+        // Split up to stay under `json!`'s recursion limit.
+        let parts = [
+            json!({
+                "id": 1, "name": "Debian", "hash_string": "abc", "creator": "",
+                "date_created": 0, "comment": "", "download_dir": "/downloads", "added_date": 0,
+                "done_date": 0, "upload_ratio": 0.0, "eta": -1, "error": 0, "error_string": "",
+                "status": 0,
+            }),
+            json!({
+                "percent_done": 0.0, "size_when_done": 0.0, "total_size": 0.0,
+                "downloaded_ever": 0.0, "uploaded_ever": 0.0, "corrupt_ever": 0.0,
+                "piece_count": 0, "piece_size": 0.0, "pieces": "", "is_private": false,
+                "files": [], "file_stats": [], "peers": [],
+            }),
+            json!({
+                "peers_from": {
+                    "from_cache": 0, "from_dht": 0, "from_incoming": 0, "from_lpd": 0,
+                    "from_ltep": 0, "from_pex": 0, "from_tracker": 0,
+                },
+                "peers_connected": 0, "peers_getting_from_us": 0, "peers_sending_to_us": 0,
+                "trackers": [], "tracker_stats": [],
+            }),
+            json!({
+                "download_limit": 100, "download_limited": true, "upload_limit": 50,
+                "upload_limited": false, "honors_session_limits": true, "seed_ratio_limit": 2.0,
+                "seed_ratio_mode": 0, "seed_idle_limit": 30, "seed_idle_mode": 0,
+                "bandwidth_priority": 0, "peer_limit": 60, "queue_position": 3,
+            }),
+        ];
+        let mut object = serde_json::Map::new();
+        for part in parts {
+            if let Value::Object(part) = part {
+                object.extend(part);
+            }
+        }
+        let detail: TorrentDetail = serde_json::from_value(Value::Object(object)).unwrap();
+        assert_eq!(detail.hash_string, "abc");
+        assert_eq!(detail.limits.download_limit, 100);
+        assert_eq!(detail.limits.peer_limit, 60);
+        assert_eq!(detail.limits.queue_position, 3);
+    }
+}
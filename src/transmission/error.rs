@@ -0,0 +1,56 @@
+use thiserror::Error;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned by the Transmission RPC client.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum Error {
+    /// The daemon could not be reached at all.
+    #[error("Transmission daemon unreachable: {0}")]
+    Connect(String),
+    #[error("Transmission daemon did not respond in time")]
+    Timeout,
+    /// The daemon answered 401, i.e. RPC authentication is required or the credentials are wrong.
+    #[error("Transmission rejected the username or password")]
+    Unauthorized,
+    /// The daemon answered 409 even after we retried with the session id it handed out.
+    #[error("Transmission kept rejecting the session id")]
+    SessionConflict,
+    #[error("Transmission did not send a valid X-Transmission-Session-Id header")]
+    MissingSessionId,
+    /// `ClientBuilder` was given no Transmission URL, or one that isn't an http(s) URL.
+    #[error("Invalid Transmission URL: {0}")]
+    InvalidUrl(String),
+    /// The HTTP client could not be set up, e.g. because of an invalid CA certificate.
+    #[error("Could not set up TLS for Transmission: {0}")]
+    Tls(String),
+    #[error("HTTP request to Transmission failed: {0}")]
+    Http(String),
+    #[error("Could not decode the Transmission response: {0}")]
+    Decode(String),
+    #[error("Torrent {0} not found")]
+    TorrentNotFound(i64),
+    /// The request reached the daemon but it reported a `result` other than `success`.
+    #[error("Transmission: {0}")]
+    Rpc(String),
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Error::Timeout
+        } else if e.is_connect() {
+            Error::Connect(e.to_string())
+        } else if e.is_decode() {
+            Error::Decode(e.to_string())
+        } else {
+            Error::Http(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Decode(e.to_string())
+    }
+}
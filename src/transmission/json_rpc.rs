@@ -0,0 +1,208 @@
+//! Translation between the legacy Transmission RPC envelope and JSON-RPC 2.0, which Transmission
+//! 4.1 speaks with snake_case method and field names.
+//!
+//! The rest of the client only knows the legacy format: requests are translated on the way out,
+//! and responses are rewritten into a legacy `result`/`arguments` envelope on the way in. Their
+//! snake_case names are left as they are, since the response types take them as serde aliases.
+
+use serde_json::{Map, Value};
+
+use crate::transmission::client::Request;
+use crate::transmission::{Error, Result};
+
+/// `downloadDir`, `download-dir` and `isUTP` become `download_dir` and `is_utp`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' {
+            snake.push('_');
+        } else if c.is_ascii_uppercase() {
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            // A word starts at a capital after a lowercase letter or digit, or at the last
+            // capital of an acronym that is followed by a lowercase letter.
+            let starts_word = matches!(prev, Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit())
+                || (matches!(prev, Some(p) if p.is_ascii_uppercase())
+                    && matches!(next, Some(n) if n.is_ascii_lowercase()));
+            if starts_word {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// The JSON-RPC 2.0 request for a legacy `request`.
+pub fn request(request: &Request, id: u64) -> Result<Value> {
+    let mut json_rpc = Map::new();
+    json_rpc.insert("jsonrpc".to_string(), Value::from("2.0"));
+    json_rpc.insert(
+        "method".to_string(),
+        Value::from(snake_case(&request.method)),
+    );
+    if let Some(arguments) = &request.arguments {
+        let params = serde_json::to_value(arguments)?;
+        json_rpc.insert("params".to_string(), snake_case_params(params));
+    }
+    json_rpc.insert("id".to_string(), Value::from(id));
+    Ok(Value::Object(json_rpc))
+}
+
+/// Renames every key to snake_case. The names in `fields` refer to keys, and `ids` can be a
+/// keyword like `recently-active`, so those values are renamed too.
+fn snake_case_params(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = match (key.as_str(), value) {
+                        ("fields", Value::Array(fields)) => Value::Array(
+                            fields
+                                .into_iter()
+                                .map(|field| match field {
+                                    Value::String(field) => Value::String(snake_case(&field)),
+                                    field => field,
+                                })
+                                .collect(),
+                        ),
+                        ("ids", Value::String(keyword)) => Value::String(snake_case(&keyword)),
+                        (_, value) => snake_case_params(value),
+                    };
+                    (snake_case(&key), value)
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(snake_case_params).collect()),
+        value => value,
+    }
+}
+
+/// Rewrites a JSON-RPC 2.0 response into the legacy envelope, or returns its error.
+pub fn response(response: Value) -> Result<Value> {
+    if let Some(error) = response.get("error") {
+        // Transmission puts its own, more specific message in `data.error_string`.
+        let message = error
+            .pointer("/data/error_string")
+            .or_else(|| error.get("message"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(Error::Rpc(message.to_string()));
+    }
+    let result = response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| Error::Decode("response has neither result nor error".to_string()))?;
+    let mut legacy = Map::new();
+    legacy.insert("result".to_string(), Value::from("success"));
+    legacy.insert("arguments".to_string(), result);
+    Ok(Value::Object(legacy))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::transmission::client::{
+        GetTorrentRequest, RequestArgs, TorrentIds, TorrentIdsKeyword,
+    };
+
+    #[test]
+    fn snake_case_names() {
+        assert_eq!(snake_case("downloadDir"), "download_dir");
+        assert_eq!(snake_case("download-dir"), "download_dir");
+        assert_eq!(snake_case("torrent-get"), "torrent_get");
+        assert_eq!(snake_case("isUTP"), "is_utp");
+        assert_eq!(snake_case("peer-limit-global"), "peer_limit_global");
+        assert_eq!(snake_case("alt-speed-time-day"), "alt_speed_time_day");
+        assert_eq!(snake_case("rateDownload"), "rate_download");
+        assert_eq!(snake_case("name"), "name");
+    }
+
+    #[test]
+    fn requests_use_snake_case() {
+        let legacy = Request {
+            method: "torrent-get".to_string(),
+            tag: None,
+            arguments: Some(RequestArgs::GetTorrentArgs(GetTorrentRequest {
+                ids: Some(TorrentIds::Keyword(TorrentIdsKeyword::RecentlyActive)),
+                fields: vec!["downloadDir".to_string(), "peer-limit".to_string()],
+                format: None,
+            })),
+        };
+        assert_eq!(
+            request(&legacy, 7).unwrap(),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "torrent_get",
+                "params": {
+                    "ids": "recently_active",
+                    "fields": ["download_dir", "peer_limit"],
+                },
+                "id": 7,
+            })
+        );
+    }
+
+    #[test]
+    fn requests_keep_numeric_ids() {
+        let legacy = Request {
+            method: "torrent-get".to_string(),
+            tag: None,
+            arguments: Some(RequestArgs::GetTorrentArgs(GetTorrentRequest {
+                ids: Some(TorrentIds::List(vec![1, 2])),
+                fields: vec!["id".to_string()],
+                format: None,
+            })),
+        };
+        let json_rpc = request(&legacy, 1).unwrap();
+        assert_eq!(json_rpc["params"]["ids"], json!([1, 2]));
+    }
+
+    #[test]
+    fn responses_become_legacy_envelopes() {
+        let legacy = response(json!({
+            "jsonrpc": "2.0",
+            "result": { "download_dir": "/downloads" },
+            "id": 1,
+        }))
+        .unwrap();
+        assert_eq!(
+            legacy,
+            json!({
+                "result": "success",
+                "arguments": { "download_dir": "/downloads" },
+            })
+        );
+    }
+
+    #[test]
+    fn response_errors() {
+        let error = response(json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": -32603,
+                "message": "Internal error",
+                "data": { "error_string": "torrent not found" },
+            },
+            "id": 1,
+        }));
+        assert_eq!(error, Err(Error::Rpc("torrent not found".to_string())));
+
+        let error = response(json!({
+            "jsonrpc": "2.0",
+            "error": { "code": -32601, "message": "Method not found" },
+            "id": 1,
+        }));
+        assert_eq!(error, Err(Error::Rpc("Method not found".to_string())));
+
+        assert!(matches!(
+            response(json!({ "jsonrpc": "2.0", "id": 1 })),
+            Err(Error::Decode(_))
+        ));
+    }
+}
@@ -1,17 +1,5 @@
-use serde::{Deserialize, Serialize};
-
 pub mod client;
+mod error;
+mod json_rpc;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Request<T> {
-    method: String,
-    tag: u32,
-    arguments: T,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Response<T> {
-    arguments: T,
-    result: String,
-    tag: u32,
-}
+pub use error::{Error, Result};
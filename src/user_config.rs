@@ -1,25 +1,165 @@
 use serde::Deserialize;
-use std::{fs, io};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, fmt, fs, io};
 use tracing::debug;
 
-#[derive(Debug, Clone, Deserialize)]
+/// A string that is never printed, since the loaded config is logged with `Debug`.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(pub String);
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct UserConfig {
     pub transmission_url: String,
     pub poll_frequency_ms: usize,
+    /// Username for Transmission's RPC authentication.
+    pub username: Option<String>,
+    /// Password for Transmission's RPC authentication. Prefer `password_file` or `password_env`
+    /// to keep it out of the config file.
+    pub password: Option<Secret>,
+    /// File containing the password.
+    pub password_file: Option<PathBuf>,
+    /// Environment variable containing the password.
+    pub password_env: Option<String>,
+    /// Timeout for each RPC request to Transmission.
+    pub request_timeout_ms: u64,
+    /// Extra PEM-encoded CA certificates to trust for an HTTPS `transmission_url`.
+    pub ca_certificates: Vec<PathBuf>,
+    /// Accept any certificate, e.g. a self-signed one.
+    pub accept_invalid_certs: bool,
+    /// MaxMind-format database used to show the country of each peer, e.g. GeoLite2-Country.mmdb.
+    pub geoip_database: Option<PathBuf>,
+    /// Address the HTTP server binds to.
+    pub listen_address: SocketAddr,
+    /// Origin browsers use to reach radio-tower, e.g. `https://example.com`. Defaults to
+    /// `http://{listen_address}`.
+    pub public_url: Option<String>,
+    /// Path prefix radio-tower is served under when behind a reverse proxy, e.g. `/radio-tower`.
+    pub base_path: String,
+}
+
+impl Default for UserConfig {
+    fn default() -> Self {
+        UserConfig {
+            transmission_url: "http://localhost:9091/transmission/rpc".to_string(),
+            poll_frequency_ms: 2000,
+            username: None,
+            password: None,
+            password_file: None,
+            password_env: None,
+            request_timeout_ms: 5000,
+            ca_certificates: Vec::new(),
+            accept_invalid_certs: false,
+            geoip_database: None,
+            listen_address: ([127, 0, 0, 1], 3030).into(),
+            public_url: None,
+            base_path: "/".to_string(),
+        }
+    }
+}
+
+impl UserConfig {
+    pub fn poll_frequency(&self) -> Duration {
+        Duration::from_millis(self.poll_frequency_ms as u64)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+
+    /// Resolves the RPC password from `password_file`, `password_env` or `password`, in that
+    /// order.
+    pub fn resolve_password(&self) -> Result<Option<String>, io::Error> {
+        if let Some(path) = &self.password_file {
+            let password = fs::read_to_string(path)?;
+            return Ok(Some(password.trim_end_matches(['\r', '\n']).to_string()));
+        }
+        if let Some(var) = &self.password_env {
+            let password = env::var(var)
+                .map_err(|e| io::Error::new(io::ErrorKind::NotFound, format!("{var}: {e}")))?;
+            return Ok(Some(password));
+        }
+        Ok(self.password.as_ref().map(|password| password.0.clone()))
+    }
+
+    /// The public origin without a trailing slash.
+    pub fn public_url(&self) -> String {
+        match &self.public_url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => format!("http://{}", self.listen_address),
+        }
+    }
+
+    /// The base path with a leading slash and no trailing slash, or `/` when served at the root.
+    pub fn base_path(&self) -> String {
+        let trimmed = self.base_path.trim_matches('/');
+        if trimmed.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/{trimmed}")
+        }
+    }
+
+    /// Builds an absolute path under the base path, e.g. `ws` -> `/radio-tower/ws`.
+    pub fn path(&self, path: &str) -> String {
+        let path = path.trim_start_matches('/');
+        match self.base_path().as_str() {
+            "/" => format!("/{path}"),
+            base => format!("{base}/{path}"),
+        }
+    }
+
+    /// Strips the base path from a browser path so it can be matched by the app router.
+    pub fn route_path(&self, path: &str) -> String {
+        let base = self.base_path();
+        let stripped = if base == "/" {
+            path
+        } else {
+            path.strip_prefix(base.as_str()).unwrap_or(path)
+        };
+        if stripped.starts_with('/') {
+            stripped.to_string()
+        } else {
+            format!("/{stripped}")
+        }
+    }
+
+    pub fn websocket_url(&self) -> String {
+        let public_url = self.public_url();
+        let origin = if let Some(rest) = public_url.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = public_url.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            format!("ws://{public_url}")
+        };
+        format!("{origin}{}", self.path("ws"))
+    }
 }
 
 #[tracing::instrument]
 pub fn load_config(user_config_path: &str) -> Result<UserConfig, io::Error> {
     if fs::metadata(user_config_path).is_ok() {
-        let config_string = fs::read_to_string(&user_config_path)?;
-        let user_config: UserConfig = toml::from_str(&config_string).unwrap();
+        let config_string = fs::read_to_string(user_config_path)?;
+        let user_config: UserConfig = toml::from_str(&config_string).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{user_config_path}: {e}"),
+            )
+        })?;
         debug!("Loaded config from {:#?}", user_config_path);
         Ok(user_config)
     } else {
         debug!("Loaded default config");
-        Ok(UserConfig {
-            transmission_url: "http://localhost:9091/transmission/rpc".to_string(),
-            poll_frequency_ms: 2000,
-        })
+        Ok(UserConfig::default())
     }
 }